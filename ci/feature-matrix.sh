@@ -0,0 +1,12 @@
+#!/bin/sh
+# Builds and tests the crate with each opcode family on its own, with
+# none and with all of them, as tests have to be gated on the families
+# they parse.
+set -e
+
+for features in "" meta chardef translation emphasis multipass; do
+    echo "features: ${features:-none}"
+    cargo clippy --all-targets --no-default-features --features "$features" -- -D warnings
+    cargo test --no-default-features --features "$features"
+done
+cargo test
@@ -0,0 +1,153 @@
+//! Guessing what kind of table a parsed table is, e.g. for a table
+//! index.
+//!
+//! Tables in the liblouis repository declare this in metadata
+//! comments such as `#+type: literary` and `#+contraction: full`.
+//! Where the metadata is missing, the kind of table is guessed from
+//! its rules.
+
+use crate::interop::{CharDefinitionSource, RuleSource};
+use crate::parser::{BrailleDot, Line, Rule};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Literary,
+    Computer,
+    /// Only known from metadata.
+    Math,
+    /// A table that only defines how characters are shown on a
+    /// braille display.
+    Display,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Contraction {
+    No,
+    /// Only known from metadata.
+    Partial,
+    Full,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Classification {
+    pub kind: Kind,
+    pub contraction: Contraction,
+}
+
+/// The `#+key: value` metadata comments of a table.
+pub fn metadata<'a>(lines: &[Line<'a>]) -> Vec<(&'a str, &'a str)> {
+    lines
+        .iter()
+        .filter_map(|line| match line {
+            Line::Comment { comment } => comment.strip_prefix('+')?.split_once(':'),
+            _ => None,
+        })
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+fn kind_of(value: &str) -> Option<Kind> {
+    match value {
+        "literary" => Some(Kind::Literary),
+        "computer" => Some(Kind::Computer),
+        "math" => Some(Kind::Math),
+        "display" => Some(Kind::Display),
+        _ => None,
+    }
+}
+
+fn contraction_of(value: &str) -> Option<Contraction> {
+    match value {
+        "no" => Some(Contraction::No),
+        "partial" => Some(Contraction::Partial),
+        "full" => Some(Contraction::Full),
+        _ => None,
+    }
+}
+
+fn guess_kind(lines: &[Line]) -> Kind {
+    let rules = lines.rules();
+    if !rules.is_empty() && rules.iter().all(|rule| matches!(rule, Rule::Display { .. } | Rule::Include { .. })) {
+        return Kind::Display;
+    }
+    let eight_dots = enumset::enum_set!(BrailleDot::DOT7 | BrailleDot::DOT8);
+    let definitions = lines.char_definitions().into_iter().map(|(_, dots)| dots);
+    let displayed = rules.iter().filter_map(|rule| match rule {
+        Rule::Display { dots, .. } => Some(dots),
+        _ => None,
+    });
+    if definitions.chain(displayed).flatten().any(|cell| !cell.is_disjoint(eight_dots)) {
+        Kind::Computer
+    } else {
+        Kind::Literary
+    }
+}
+
+/// Whether any translation rule translates several characters at
+/// once, i.e. is a contraction.
+fn guess_contraction(lines: &[Line]) -> Contraction {
+    let contracted = lines.rules().iter().any(|rule| match rule {
+        Rule::Largesign { word, .. } | Rule::Syllable { word, .. } | Rule::Joinword { word, .. } => word.chars().count() > 1,
+        Rule::Always { chars, .. } | Rule::Word { chars, .. } | Rule::Contraction { chars }
+        | Rule::Begword { chars, .. } | Rule::Midword { chars, .. } | Rule::Endword { chars, .. }
+        | Rule::Partword { chars, .. } | Rule::Begmidword { chars, .. } | Rule::Midendword { chars, .. }
+        | Rule::Prfxword { chars, .. } | Rule::Sufword { chars, .. } | Rule::Lowword { chars, .. } => chars.chars().count() > 1,
+        _ => false,
+    });
+    if contracted {
+        Contraction::Full
+    } else {
+        Contraction::No
+    }
+}
+
+/// Classifies a table by its metadata, falling back to its rules.
+pub fn classify(lines: &[Line]) -> Classification {
+    let metadata = metadata(lines);
+    let declared = |key: &str| metadata.iter().rev().find(|(k, _)| *k == key).map(|(_, value)| *value);
+    Classification {
+        kind: declared("type").and_then(kind_of).unwrap_or_else(|| guess_kind(lines)),
+        contraction: declared("contraction").and_then(contraction_of).unwrap_or_else(|| guess_contraction(lines)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::table;
+
+    #[test]
+    fn metadata_test() {
+        let (_, lines) = table("#+language: de\n# just a comment\n#+type:math\n").unwrap();
+        assert_eq!(metadata(&lines), vec![("language", "de"), ("type", "math")]);
+        assert_eq!(classify(&lines), Classification { kind: Kind::Math, contraction: Contraction::No });
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn guess_test() {
+        let (_, lines) = table("display a 1\ndisplay b 12\n").unwrap();
+        assert_eq!(classify(&lines).kind, Kind::Display);
+        let (_, lines) = table("punctuation . 256\ndisplay \\x7f 12378\n").unwrap();
+        assert_eq!(classify(&lines).kind, Kind::Computer);
+        let (_, lines) = table("punctuation . 256\njoinword to 235\n").unwrap();
+        assert_eq!(classify(&lines), Classification { kind: Kind::Literary, contraction: Contraction::Full });
+        let (_, lines) = table("letter a 1\nalways the 2346\n").unwrap();
+        assert_eq!(classify(&lines).contraction, Contraction::Full);
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn guess_word_position_test() {
+        let (_, lines) = table("sufword ing 346\npartword ch 16\n").unwrap();
+        assert_eq!(classify(&lines).contraction, Contraction::Full);
+        let (_, lines) = table("sufword a 1\nbegword b 12\n").unwrap();
+        assert_eq!(classify(&lines).contraction, Contraction::No);
+    }
+
+    #[test]
+    fn metadata_overrides_guess_test() {
+        let (_, lines) = table("#+contraction: partial\njoinword to 235\n").unwrap();
+        assert_eq!(classify(&lines).contraction, Contraction::Partial);
+    }
+}
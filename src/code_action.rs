@@ -0,0 +1,105 @@
+//! Code actions that rewrite parts of a table.
+
+use std::ops::Range;
+
+use crate::edit::TextEdit;
+use crate::opcode::OperandKind;
+use crate::parser::{dots, dots_to_string, dots_to_unicode};
+use crate::table::Table;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Notation {
+    /// Dot numbers such as `123-45`.
+    Numbers,
+    /// Unicode braille patterns such as `⠇⠘`.
+    Unicode,
+}
+
+impl Notation {
+    /// The notation a dots operand is written in.
+    fn of(text: &str) -> Notation {
+        match text.chars().next() {
+            Some('\u{2800}'..='\u{28ff}') => Notation::Unicode,
+            _ => Notation::Numbers,
+        }
+    }
+}
+
+/// Converts the dots operands of all rules on lines intersecting
+/// `range` to `notation`. Operands that already use the notation or
+/// cannot be represented in it are left alone.
+pub fn convert_dots(table: &Table, range: Range<usize>, notation: Notation) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    for line in 0..table.lines().len() {
+        let span = table.span(line).unwrap();
+        if span.end < range.start || span.start > range.end {
+            continue;
+        }
+        for operand in table.operands(line) {
+            if operand.kind != OperandKind::Dots {
+                continue;
+            }
+            let text = &table.source()[operand.span.clone()];
+            if Notation::of(text) == notation {
+                continue;
+            }
+            let converted = match dots(text) {
+                Ok(("", parsed)) => match notation {
+                    Notation::Numbers => Some(dots_to_string(&parsed)),
+                    Notation::Unicode => dots_to_unicode(&parsed),
+                },
+                _ => None,
+            };
+            if let Some(new_text) = converted {
+                edits.push(TextEdit { range: operand.span, new_text });
+            }
+        }
+    }
+    edits
+}
+
+#[cfg(all(test, any(feature = "chardef", feature = "translation")))]
+mod tests {
+    use super::*;
+    use crate::edit::apply;
+
+    const SOURCE: &str = concat!("display a 1\n",
+                                 "# a comment\n",
+                                 "endcapsphrase after 45-9\n",
+                                 "joinword to ⠖\n");
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn to_unicode_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        let edits = convert_dots(&table, 0..SOURCE.len(), Notation::Unicode);
+        assert_eq!(edits, vec![TextEdit { range: 10..11, new_text: "⠁".to_string() }]);
+        assert_eq!(apply(SOURCE, &edits),
+                   concat!("display a ⠁\n",
+                           "# a comment\n",
+                           "endcapsphrase after 45-9\n",
+                           "joinword to ⠖\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn to_numbers_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        let edits = convert_dots(&table, 50..51, Notation::Numbers);
+        assert_eq!(apply(SOURCE, &edits),
+                   concat!("display a 1\n",
+                           "# a comment\n",
+                           "endcapsphrase after 45-9\n",
+                           "joinword to 235\n"));
+        assert_eq!(convert_dots(&table, 0..5, Notation::Numbers), vec![]);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn same_notation_test() {
+        let (_, table) = Table::parse(concat!("display a 21\n",
+                                              "display b ⠃\n")).unwrap();
+        assert_eq!(convert_dots(&table, 0..11, Notation::Numbers), vec![]);
+        assert_eq!(convert_dots(&table, 13..25, Notation::Unicode), vec![]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Completion candidates for a partially typed rule, e.g. for editor
+//! autocompletion.
+
+use enumset::EnumSet;
+
+use crate::opcode::{OperandKind, Opcode};
+use crate::parser::{Prefix, Prefixes};
+
+#[derive(PartialEq, Debug, Default)]
+pub struct Completion {
+    /// Opcode keywords matching the word under the cursor.
+    pub keywords: Vec<&'static str>,
+    /// Prefixes matching the word under the cursor.
+    pub prefixes: Vec<&'static str>,
+    /// The kind of operand expected at the cursor.
+    pub operand: Option<OperandKind>,
+}
+
+fn prefix_from_keyword(word: &str) -> Option<Prefix> {
+    EnumSet::<Prefix>::all().iter().find(|prefix| prefix.keyword() == word)
+}
+
+fn is_valid_combination(prefixes: Prefixes) -> bool {
+    !prefixes.is_superset(Prefix::Noback | Prefix::Nofor)
+}
+
+/// Returns the completion candidates for `line` with the cursor at
+/// byte offset `cursor`.
+pub fn complete(line: &str, cursor: usize) -> Completion {
+    let before = line.get(..cursor).unwrap_or(line);
+    if before.trim_start().starts_with('#') {
+        return Completion::default();
+    }
+    let mut words: Vec<&str> = before.split_whitespace().collect();
+    let partial = if before.ends_with(char::is_whitespace) { "" } else { words.pop().unwrap_or("") };
+
+    let mut prefixes = Prefixes::empty();
+    let mut words = words.into_iter();
+    let keyword = loop {
+        match words.next() {
+            Some(word) => match prefix_from_keyword(word) {
+                Some(prefix) => prefixes |= prefix,
+                None => break Some(word),
+            },
+            None => break None,
+        }
+    };
+
+    match keyword {
+        None => Completion {
+            keywords: Opcode::ALL
+                .iter()
+                .filter(|opcode| prefixes.is_empty() || opcode.accepts_prefixes())
+                .map(|opcode| opcode.keyword())
+                .filter(|keyword| keyword.starts_with(partial))
+                .collect(),
+            prefixes: EnumSet::<Prefix>::all()
+                .iter()
+                .filter(|prefix| !prefixes.contains(*prefix) && is_valid_combination(prefixes | *prefix))
+                .map(|prefix| prefix.keyword())
+                .filter(|keyword| keyword.starts_with(partial))
+                .collect(),
+            operand: None,
+        },
+        Some(keyword) => Completion {
+            operand: Opcode::from_keyword(keyword).and_then(|opcode| opcode.operands().get(words.count()).copied()),
+            ..Completion::default()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyword_test() {
+        assert_eq!(complete("begcaps", 4),
+                   Completion { keywords: vec!["begcapsword", "begcaps", "begcapsphrase"], ..Completion::default() });
+        assert_eq!(complete("nocross begc", 12),
+                   Completion { keywords: vec!["begcapsword"], ..Completion::default() });
+        assert_eq!(complete("no", 2),
+                   Completion { keywords: vec!["noletsign", "noletsignbefore", "noletsignafter", "nocontractsign", "nonumsign", "nocont"], prefixes: vec!["noback", "nofor", "nocross"], ..Completion::default() });
+        assert_eq!(complete("noback no", 9),
+                   Completion { prefixes: vec!["nocross"], ..Completion::default() });
+    }
+
+    #[test]
+    fn operand_test() {
+        assert_eq!(complete("endcapsphrase ", 14).operand, Some(OperandKind::Position));
+        assert_eq!(complete("endcapsphrase after 4", 21).operand, Some(OperandKind::Dots));
+        assert_eq!(complete("nocross display a ", 18).operand, Some(OperandKind::Dots));
+        assert_eq!(complete("endcapsphrase after 45 ", 23).operand, None);
+        assert_eq!(complete("foo ", 4), Completion::default());
+    }
+
+    #[test]
+    fn comment_test() {
+        assert_eq!(complete("# begc", 6), Completion::default());
+    }
+}
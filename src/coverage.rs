@@ -0,0 +1,94 @@
+//! How much of a table this crate can represent.
+//!
+//! The parser stops at the first line it does not understand, which
+//! makes it hard to tell how far off a table is. The coverage report
+//! looks at every line on its own and counts, per opcode, the rules
+//! that parsed and those that did not, as well as the keywords that
+//! are not known opcodes at all.
+
+use std::collections::BTreeMap;
+
+use crate::lexer::{tokenize_line, TokenKind};
+use crate::opcode::Opcode;
+use crate::parser::line;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OpcodeCount {
+    pub opcode: Opcode,
+    pub parsed: usize,
+    /// Rules with this opcode that did not parse, e.g. because of
+    /// operands the parser does not support yet.
+    pub failed: usize,
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct CoverageReport {
+    /// The opcodes used in the table, in the order of `Opcode::ALL`.
+    pub opcodes: Vec<OpcodeCount>,
+    /// The keywords that are not known opcodes and how often they
+    /// occur, sorted by keyword.
+    pub unsupported: Vec<(String, usize)>,
+}
+
+impl CoverageReport {
+    /// Whether every rule of the table parsed.
+    pub fn is_complete(&self) -> bool {
+        self.unsupported.is_empty() && self.opcodes.iter().all(|count| count.failed == 0)
+    }
+}
+
+/// Counts the opcodes of the rules in `source`, one rule per line.
+pub fn coverage_report(source: &str) -> CoverageReport {
+    let mut counts: Vec<OpcodeCount> =
+        Opcode::ALL.iter().map(|opcode| OpcodeCount { opcode: *opcode, parsed: 0, failed: 0 }).collect();
+    let mut unsupported = BTreeMap::new();
+    for text in source.lines() {
+        let tokens = tokenize_line(text);
+        let Some(token) = tokens.iter().find(|token| token.kind != TokenKind::Prefix) else {
+            continue;
+        };
+        match token.kind {
+            TokenKind::Opcode(opcode) => {
+                let count = &mut counts[Opcode::ALL.iter().position(|o| *o == opcode).unwrap()];
+                match line(&format!("{}\n", text)) {
+                    Ok(_) => count.parsed += 1,
+                    Err(_) => count.failed += 1,
+                }
+            }
+            TokenKind::Unknown => {
+                let keyword = text[token.span.clone()].split_whitespace().next().unwrap_or("");
+                *unsupported.entry(keyword.to_string()).or_insert(0) += 1;
+            }
+            _ => (),
+        }
+    }
+    CoverageReport {
+        opcodes: counts.into_iter().filter(|count| count.parsed + count.failed > 0).collect(),
+        unsupported: unsupported.into_iter().collect(),
+    }
+}
+
+#[cfg(all(test, feature = "chardef", feature = "translation"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_report_test() {
+        let source = concat!("# a comment\n",
+                             "display a 1\n",
+                             "noback display b 12\n",
+                             "display c\n",
+                             "\n",
+                             "frobnicate foo 12\n",
+                             "frobnicate bar 12\n",
+                             "twiddle \"a\" @1\n",
+                             "joinword to 235\n");
+        let report = coverage_report(source);
+        assert_eq!(report.opcodes,
+                   vec![OpcodeCount { opcode: Opcode::Display, parsed: 2, failed: 1 },
+                        OpcodeCount { opcode: Opcode::Joinword, parsed: 1, failed: 0 }]);
+        assert_eq!(report.unsupported, vec![("frobnicate".to_string(), 2), ("twiddle".to_string(), 1)]);
+        assert!(!report.is_complete());
+        assert!(coverage_report("display a 1\n").is_complete());
+    }
+}
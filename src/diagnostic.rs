@@ -0,0 +1,275 @@
+//! Diagnostics for table authors.
+//!
+//! Every diagnostic has a stable code, e.g. `E001`, that tools and
+//! documentation can refer to, and a message key with named
+//! arguments. The English messages are built in. With the `l10n`
+//! feature, messages can be rendered from a `Catalog` in any other
+//! language, while the codes stay the same.
+
+use std::ops::Range;
+
+#[cfg(feature = "l10n")]
+use std::collections::HashMap;
+
+use nom::Err;
+
+use crate::lexer::{tokenize_line, TokenKind};
+use crate::opcode::Opcode;
+use crate::parser::opcodes::enabled;
+use crate::parser::{line, Line};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+macro_rules! codes {
+    ($($variant:ident => $code:literal, $severity:ident, $key:literal, $message:literal;)*) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum Code {
+            $($variant,)*
+        }
+
+        impl Code {
+            pub const ALL: &'static [Code] = &[$(Code::$variant,)*];
+
+            /// The stable code, e.g. `E001`.
+            pub fn code(self) -> &'static str {
+                match self {
+                    $(Code::$variant => $code,)*
+                }
+            }
+
+            pub fn severity(self) -> Severity {
+                match self {
+                    $(Code::$variant => Severity::$severity,)*
+                }
+            }
+
+            /// The key of the message in a catalog.
+            pub fn key(self) -> &'static str {
+                match self {
+                    $(Code::$variant => $key,)*
+                }
+            }
+
+            /// The English message, with `{name}` placeholders for the
+            /// arguments.
+            pub fn message(self) -> &'static str {
+                match self {
+                    $(Code::$variant => $message,)*
+                }
+            }
+        }
+    };
+}
+
+codes! {
+    UnknownOpcode => "E001", Error, "unknown-opcode", "unknown opcode `{keyword}`";
+    InvalidOperands => "E002", Error, "invalid-operands", "invalid operands for opcode `{opcode}`";
+    InvalidLine => "E003", Error, "invalid-line", "cannot parse line";
+    UnexpectedScript => "W001", Warning, "unexpected-script",
+        "character `{char}` is {script}, which is not a script of language `{language}`";
+    DeprecatedOpcode => "W002", Warning, "deprecated-opcode", "opcode `{opcode}` is deprecated, use {replacement} instead";
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Diagnostic {
+    pub code: Code,
+    /// The byte span in the source.
+    pub span: Range<usize>,
+    /// The named arguments of the message.
+    pub args: Vec<(&'static str, String)>,
+}
+
+fn render(template: &str, args: &[(&'static str, String)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |message, (name, value)| message.replace(&format!("{{{}}}", name), value))
+}
+
+impl Diagnostic {
+    /// The message in English.
+    pub fn message(&self) -> String {
+        render(self.code.message(), &self.args)
+    }
+
+    /// The message from `catalog`, or in English if the catalog does
+    /// not have it.
+    #[cfg(feature = "l10n")]
+    pub fn localized(&self, catalog: &Catalog) -> String {
+        match catalog.messages.get(self.code.key()) {
+            Some(template) => render(template, &self.args),
+            None => self.message(),
+        }
+    }
+}
+
+/// The messages of one language.
+#[cfg(feature = "l10n")]
+#[derive(PartialEq, Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+#[cfg(feature = "l10n")]
+impl Catalog {
+    /// Reads a catalog with one `key = message` per line. Empty lines
+    /// and lines starting with `#` are ignored.
+    pub fn parse(text: &str) -> Catalog {
+        let messages = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, message)| (key.trim().to_string(), message.trim().to_string()))
+            .collect();
+        Catalog { messages }
+    }
+
+    /// The keys of `Code::ALL` that the catalog has no message for.
+    pub fn missing(&self) -> Vec<&'static str> {
+        Code::ALL.iter().map(|code| code.key()).filter(|key| !self.messages.contains_key(*key)).collect()
+    }
+}
+
+/// The opcodes that replace a deprecated opcode.
+fn replacement(opcode: Opcode) -> Option<&'static str> {
+    match opcode {
+        Opcode::Uplow => Some("`uppercase` and `lowercase`"),
+        Opcode::Class => Some("`attribute`"),
+        _ => None,
+    }
+}
+
+/// Diagnoses a single line without line ending, starting at `offset`
+/// in the source.
+fn diagnose_line(text: &str, offset: usize) -> Option<Diagnostic> {
+    let input = format!("{}\n", text);
+    match line(&input) {
+        Ok((_, Line::Rule { rule, .. })) => {
+            let replacement = replacement(rule.opcode())?;
+            let token = tokenize_line(text).into_iter().find(|token| matches!(token.kind, TokenKind::Opcode(_)))?;
+            Some(Diagnostic {
+                code: Code::DeprecatedOpcode,
+                span: offset + token.span.start..offset + token.span.end,
+                args: vec![("opcode", rule.opcode().keyword().to_string()), ("replacement", replacement.to_string())],
+            })
+        }
+        Ok(_) => None,
+        Err(Err::Failure(e)) => {
+            let position = (input.len() - e.input.len()).min(text.len());
+            let tokens: Vec<_> = tokenize_line(text).into_iter().filter(|token| token.kind != TokenKind::Comment).collect();
+            let opcode = tokens.iter().find_map(|token| match token.kind {
+                TokenKind::Opcode(opcode) => Some(opcode),
+                _ => None,
+            })?;
+            // the operand the parser failed in, or the last token if an
+            // operand is missing at the end of the line
+            let token = tokens.iter().find(|token| token.span.end > position).or(tokens.last())?;
+            Some(Diagnostic {
+                code: Code::InvalidOperands,
+                span: offset + token.span.start..offset + token.span.end,
+                args: vec![("opcode", opcode.keyword().to_string())],
+            })
+        }
+        // opcodes of disabled families are as unknown as misspelled ones
+        Err(_) => match tokenize_line(text).into_iter().find(|token| {
+            token.kind == TokenKind::Unknown || matches!(token.kind, TokenKind::Opcode(opcode) if !enabled(opcode.category()))
+        }) {
+            Some(token) => {
+                let keyword = text[token.span.clone()].split_whitespace().next().unwrap_or("");
+                let start = offset + token.span.start;
+                Some(Diagnostic {
+                    code: Code::UnknownOpcode,
+                    span: start..start + keyword.len(),
+                    args: vec![("keyword", keyword.to_string())],
+                })
+            }
+            None => Some(Diagnostic { code: Code::InvalidLine, span: offset..offset + text.len(), args: vec![] }),
+        },
+    }
+}
+
+/// Diagnoses every line of `source`. Unlike `table`, this does not
+/// stop at the first line that does not parse.
+pub fn diagnostics(source: &str) -> Vec<Diagnostic> {
+    let mut offset = 0;
+    let mut diagnostics = Vec::new();
+    for text in source.split_inclusive('\n') {
+        diagnostics.extend(diagnose_line(text.trim_end_matches(['\r', '\n']), offset));
+        offset += text.len();
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn diagnostics_test() {
+        let source = "display a 1\nnoback foo a 1\ndisplay a\n  display a 1\n";
+        assert_eq!(diagnostics(source),
+                   vec![Diagnostic { code: Code::UnknownOpcode, span: 19..22, args: vec![("keyword", "foo".to_string())] },
+                        Diagnostic { code: Code::InvalidOperands, span: 35..36, args: vec![("opcode", "display".to_string())] },
+                        Diagnostic { code: Code::InvalidLine, span: 37..50, args: vec![] }]);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn invalid_operands_span_test() {
+        let spans = |source| diagnostics(source).into_iter().map(|d| (d.code, d.span)).collect::<Vec<_>>();
+        assert_eq!(spans("display a x 1\n"), vec![(Code::InvalidOperands, 10..11)]);
+        assert_eq!(spans("noback display ab xy 12\n"), vec![(Code::InvalidOperands, 18..20)]);
+        assert_eq!(spans("display\n"), vec![(Code::InvalidOperands, 0..7)]);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn deprecated_test() {
+        let diagnostics = diagnostics("# old\nuplow Aa 17,1\n");
+        assert_eq!(diagnostics.iter().map(|d| (d.code, d.span.clone())).collect::<Vec<_>>(),
+                   vec![(Code::DeprecatedOpcode, 6..11)]);
+        assert_eq!(diagnostics[0].code.severity(), Severity::Warning);
+        assert_eq!(diagnostics[0].message(), "opcode `uplow` is deprecated, use `uppercase` and `lowercase` instead");
+        assert_eq!(super::diagnostics("class vowels aeiou\n")[0].message(), "opcode `class` is deprecated, use `attribute` instead");
+    }
+
+    #[test]
+    fn message_test() {
+        let diagnostic = &diagnostics("foo a 1")[0];
+        assert_eq!(diagnostic.code.code(), "E001");
+        assert_eq!(diagnostic.code.severity(), Severity::Error);
+        assert_eq!(diagnostic.message(), "unknown opcode `foo`");
+    }
+
+    #[test]
+    #[cfg(not(feature = "emphasis"))]
+    fn disabled_family_test() {
+        let diagnostic = &diagnostics("begcaps 6-6\n")[0];
+        assert_eq!((diagnostic.code, diagnostic.span.clone()), (Code::UnknownOpcode, 0..7));
+        assert_eq!(diagnostic.message(), "unknown opcode `begcaps`");
+    }
+
+    #[test]
+    fn codes_test() {
+        let codes: Vec<&str> = Code::ALL.iter().map(|code| code.code()).collect();
+        let mut sorted = codes.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(codes, sorted);
+    }
+
+    #[test]
+    #[cfg(all(feature = "l10n", feature = "chardef"))]
+    fn catalog_test() {
+        let catalog = Catalog::parse("# Deutsch\n\nunknown-opcode = unbekannter Opcode `{keyword}`\n");
+        let diagnostic = &diagnostics("foo a 1")[0];
+        assert_eq!(diagnostic.localized(&catalog), "unbekannter Opcode `foo`");
+        let diagnostic = &diagnostics("display a\n")[0];
+        assert_eq!(diagnostic.localized(&catalog), "invalid operands for opcode `display`");
+        assert_eq!(catalog.missing(), vec!["invalid-operands", "invalid-line", "unexpected-script", "deprecated-opcode"]);
+    }
+}
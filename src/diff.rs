@@ -0,0 +1,156 @@
+//! Unified diffs between two versions of a table, e.g. to open a pull
+//! request for changes made with a `TableEditor`.
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Op {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// The number of unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// A line diff of `old` and `new`. Common leading and trailing lines
+/// are skipped before computing the longest common subsequence, as
+/// edits of tables are usually small.
+fn ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+    // lengths[i][j] is the length of the longest common subsequence of
+    // a[i..] and b[j..]
+    let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push(Op::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            ops.push(Op::Delete(prefix + i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    ops.extend((0..suffix).map(|k| Op::Equal(old.len() - suffix + k, new.len() - suffix + k)));
+    ops
+}
+
+fn push_line(patch: &mut String, marker: char, line: &str) {
+    patch.push(marker);
+    patch.push_str(line);
+    if !line.ends_with('\n') {
+        patch.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// A hunk range in the header, with one based line numbers.
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// A unified diff of `old` and `new` for the file at `path`, or an
+/// empty string if they are equal.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = ops(&old_lines, &new_lines);
+    let changes: Vec<usize> = (0..ops.len()).filter(|k| !matches!(ops[*k], Op::Equal(..))).collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+    let mut patch = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut k = 0;
+    while k < changes.len() {
+        let first = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] - changes[k] <= 2 * CONTEXT + 1 {
+            k += 1;
+        }
+        let hunk = &ops[first.saturating_sub(CONTEXT)..(changes[k] + CONTEXT + 1).min(ops.len())];
+        k += 1;
+        let (mut old_start, mut new_start) = (None, None);
+        let (mut old_count, mut new_count) = (0, 0);
+        for op in hunk {
+            if let Op::Equal(i, _) | Op::Delete(i) = op {
+                old_start.get_or_insert(*i);
+                old_count += 1;
+            }
+            if let Op::Equal(_, j) | Op::Insert(j) = op {
+                new_start.get_or_insert(*j);
+                new_count += 1;
+            }
+        }
+        // with context lines, a side of a hunk is only empty if the file
+        // is empty
+        patch.push_str(&format!("@@ -{} +{} @@\n",
+                                range(old_start.unwrap_or(0), old_count),
+                                range(new_start.unwrap_or(0), new_count)));
+        for op in hunk {
+            match op {
+                Op::Equal(i, _) => push_line(&mut patch, ' ', old_lines[*i]),
+                Op::Delete(i) => push_line(&mut patch, '-', old_lines[*i]),
+                Op::Insert(j) => push_line(&mut patch, '+', new_lines[*j]),
+            }
+        }
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+
+    #[test]
+    fn unified_diff_test() {
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(unified_diff("foo.ctb", OLD, new),
+                   concat!("--- a/foo.ctb\n",
+                           "+++ b/foo.ctb\n",
+                           "@@ -1,5 +1,5 @@\n",
+                           " a\n",
+                           "-b\n",
+                           "+B\n",
+                           " c\n",
+                           " d\n",
+                           " e\n",
+                           "@@ -8,3 +8,4 @@\n",
+                           " h\n",
+                           " i\n",
+                           " j\n",
+                           "+k\n"));
+        assert_eq!(unified_diff("foo.ctb", OLD, OLD), "");
+    }
+
+    #[test]
+    fn merged_hunks_test() {
+        let new = "a\nb\nC\nd\ne\nf\nG\nh\ni\nj\n";
+        assert_eq!(unified_diff("foo.ctb", OLD, new).matches("@@ -").count(), 1);
+    }
+
+    #[test]
+    fn empty_and_no_newline_test() {
+        assert_eq!(unified_diff("foo.ctb", "", "a"),
+                   "--- a/foo.ctb\n+++ b/foo.ctb\n@@ -0,0 +1 @@\n+a\n\\ No newline at end of file\n");
+        assert_eq!(unified_diff("foo.ctb", "a\nb\n", "a\n"),
+                   "--- a/foo.ctb\n+++ b/foo.ctb\n@@ -1,2 +1 @@\n a\n-b\n");
+    }
+}
@@ -0,0 +1,125 @@
+//! The effective mapping from characters to the dots shown on a
+//! braille display.
+
+use std::collections::HashMap;
+
+use nom::combinator::all_consuming;
+
+use crate::interop::RuleSource;
+use crate::parser::{single_char, BrailleChars, Rule};
+
+/// A character that was defined more than once with different dots.
+#[derive(PartialEq, Debug)]
+pub struct Conflict {
+    pub ch: char,
+    pub kept: BrailleChars,
+    pub ignored: BrailleChars,
+}
+
+#[derive(PartialEq, Debug, Default)]
+pub struct DisplayMap {
+    pub map: HashMap<char, BrailleChars>,
+    pub conflicts: Vec<Conflict>,
+    /// The characters of `display` rules that are not a single
+    /// character, which the map cannot hold.
+    pub ignored: Vec<String>,
+}
+
+fn insert(map: &mut HashMap<char, BrailleChars>, conflicts: &mut Vec<Conflict>, ch: char, dots: &BrailleChars) {
+    match map.get(&ch) {
+        Some(kept) if kept != dots => conflicts.push(Conflict { ch, kept: kept.clone(), ignored: dots.clone() }),
+        Some(_) => (),
+        None => {
+            map.insert(ch, dots.clone());
+        }
+    }
+}
+
+/// Builds the display map of a table.
+///
+/// The rules are expected in include-resolved order, i.e. with the
+/// rules of an included table in place of the `include` rule. A
+/// `display` rule overrides the character definitions of its
+/// character, which may be escaped like in character definitions,
+/// e.g. `\s`. Otherwise the first definition of a character wins and
+/// any later definition with different dots is reported as a conflict.
+pub fn display_map<'a, S: RuleSource<'a> + ?Sized>(source: &S) -> DisplayMap {
+    let mut displays = HashMap::new();
+    let mut definitions = HashMap::new();
+    let mut conflicts = Vec::new();
+    let mut ignored = Vec::new();
+    for rule in source.rules() {
+        match rule {
+            Rule::Display { chars, dots, .. } => match all_consuming(single_char)(chars) {
+                Ok((_, ch)) => insert(&mut displays, &mut conflicts, ch, dots),
+                Err(_) => ignored.push(chars.to_string()),
+            },
+            _ => {
+                for (ch, dots) in rule.char_definitions() {
+                    insert(&mut definitions, &mut conflicts, ch, dots);
+                }
+            }
+        }
+    }
+    definitions.extend(displays);
+    DisplayMap { map: definitions, conflicts, ignored }
+}
+
+#[cfg(all(test, feature = "chardef"))]
+mod tests {
+    use super::*;
+    use crate::parser::{table, BrailleDot};
+    use enumset::enum_set;
+
+    #[test]
+    fn display_map_test() {
+        let (_, lines) = table(concat!("punctuation . 256\n",
+                                       "digit 1 1\n",
+                                       "display . 46\n")).unwrap();
+        let display_map = display_map(&lines);
+        assert_eq!(display_map.map.len(), 2);
+        assert_eq!(display_map.map[&'.'], vec![BrailleDot::DOT4 | BrailleDot::DOT6]);
+        assert_eq!(display_map.map[&'1'], vec![enum_set!(BrailleDot::DOT1)]);
+        assert_eq!(display_map.conflicts, vec![]);
+    }
+
+    #[test]
+    fn uplow_test() {
+        let (_, lines) = table(concat!("uplow Aa 17,1\n",
+                                       "lowercase a 1\n",
+                                       "display A 47\n")).unwrap();
+        let display_map = display_map(&lines);
+        assert_eq!(display_map.map[&'a'], vec![enum_set!(BrailleDot::DOT1)]);
+        assert_eq!(display_map.map[&'A'], vec![BrailleDot::DOT4 | BrailleDot::DOT7]);
+        assert_eq!(display_map.conflicts, vec![]);
+    }
+
+    #[test]
+    fn conflicts_test() {
+        let (_, lines) = table(concat!("punctuation . 256\n",
+                                       "punctuation . 256\n",
+                                       "punctuation . 46\n",
+                                       "display haha 1\n")).unwrap();
+        let display_map = display_map(&lines);
+        assert_eq!(display_map.map[&'.'], vec![BrailleDot::DOT2 | BrailleDot::DOT5 | BrailleDot::DOT6]);
+        assert_eq!(display_map.conflicts,
+                   vec![Conflict { ch: '.',
+                                   kept: vec![BrailleDot::DOT2 | BrailleDot::DOT5 | BrailleDot::DOT6],
+                                   ignored: vec![BrailleDot::DOT4 | BrailleDot::DOT6] }]);
+        assert_eq!(display_map.ignored, vec!["haha".to_string()]);
+    }
+
+    #[test]
+    fn escaped_display_test() {
+        let (_, lines) = table(concat!("space \\s 0\n",
+                                       "display \\s 0\n",
+                                       "display \\x2800 0\n",
+                                       "display a 1\n")).unwrap();
+        let display_map = display_map(&lines);
+        assert_eq!(display_map.map.len(), 3);
+        assert_eq!(display_map.map[&' '], vec![enum_set!(BrailleDot::DOT0)]);
+        assert_eq!(display_map.map[&'\u{2800}'], vec![enum_set!(BrailleDot::DOT0)]);
+        assert_eq!(display_map.conflicts, vec![]);
+        assert_eq!(display_map.ignored, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,39 @@
+//! Text edits on the source of a table, as produced by code actions
+//! and refactorings.
+
+use std::ops::Range;
+
+/// Replaces the bytes in `range` with `new_text`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub new_text: String,
+}
+
+/// Applies non-overlapping edits to `source`.
+pub fn apply(source: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    edits.sort_by_key(|edit| edit.range.start);
+    let mut result = String::with_capacity(source.len());
+    let mut last = 0;
+    for edit in edits {
+        result.push_str(&source[last..edit.range.start]);
+        result.push_str(&edit.new_text);
+        last = edit.range.end;
+    }
+    result.push_str(&source[last..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_test() {
+        let edits = vec![TextEdit { range: 6..11, new_text: "there".to_string() },
+                         TextEdit { range: 0..0, new_text: "> ".to_string() }];
+        assert_eq!(apply("hello world", &edits), "> hello there");
+        assert_eq!(apply("hello world", &[]), "hello world");
+    }
+}
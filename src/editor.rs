@@ -0,0 +1,180 @@
+//! Editing the rules of a table without reformatting the rest of it.
+//!
+//! `TableEditor` owns the source of a table. Every change is made as
+//! a single minimal `TextEdit` on the source, which is returned so
+//! that it can be forwarded to an editor. The AST is parsed from the
+//! current source on demand. All changes together can also be turned
+//! into a patch.
+
+use std::ops::Range;
+
+use nom::IResult;
+
+use crate::diff::unified_diff;
+use crate::edit::{apply, TextEdit};
+use crate::lexer::{tokenize_line, TokenKind};
+use crate::parser::{Line, Rule};
+use crate::table::Table;
+
+#[derive(PartialEq, Debug)]
+pub struct TableEditor {
+    original: String,
+    source: String,
+}
+
+impl TableEditor {
+    pub fn new(source: impl Into<String>) -> TableEditor {
+        let source = source.into();
+        TableEditor { original: source.clone(), source }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// A unified diff of all changes made so far to the file at
+    /// `path`.
+    pub fn patch(&self, path: &str) -> String {
+        unified_diff(path, &self.original, &self.source)
+    }
+
+    /// Parses the current source, see `Table::parse`.
+    pub fn table(&self) -> IResult<&str, Table<'_>> {
+        Table::parse(&self.source)
+    }
+
+    /// The spans of the lines of the parsed part of the source,
+    /// including line endings.
+    fn lines(&self) -> Option<Vec<Range<usize>>> {
+        let (rest, table) = self.table().ok()?;
+        let end = self.source.len() - rest.len();
+        let starts: Vec<usize> = (0..table.lines().len()).map(|line| table.span(line).unwrap().start).collect();
+        Some(starts
+             .iter()
+             .enumerate()
+             .map(|(line, start)| *start..starts.get(line + 1).copied().unwrap_or(end))
+             .collect())
+    }
+
+    /// The span of the rule on a line without its end comment, or
+    /// `None` if the line holds no rule.
+    fn rule_span(&self, line: usize) -> Option<Range<usize>> {
+        let (_, table) = self.table().ok()?;
+        let span = table.span(line)?;
+        match table.lines()[line] {
+            Line::Rule { .. } => {
+                let tokens = tokenize_line(&self.source[span.clone()]);
+                let end = tokens.iter().rev().find(|token| token.kind != TokenKind::Comment)?.span.end;
+                Some(span.start..span.start + end)
+            }
+            _ => None,
+        }
+    }
+
+    fn edit(&mut self, range: Range<usize>, new_text: String) -> Option<TextEdit> {
+        let edit = TextEdit { range, new_text };
+        self.source = apply(&self.source, std::slice::from_ref(&edit));
+        Some(edit)
+    }
+
+    /// Inserts `rule` as a new line before the zero based `line`, or
+    /// after the last parsed line if `line` is the number of lines.
+    pub fn insert(&mut self, line: usize, rule: &Rule) -> Option<TextEdit> {
+        let lines = self.lines()?;
+        let offset = match lines.get(line) {
+            Some(span) => span.start,
+            None if line == lines.len() => lines.last().map_or(0, |span| span.end),
+            None => return None,
+        };
+        self.edit(offset..offset, format!("{}\n", rule))
+    }
+
+    /// Removes the rule on a line including its line ending. Returns
+    /// `None` if the line does not exist or holds no rule.
+    pub fn remove(&mut self, line: usize) -> Option<TextEdit> {
+        self.rule_span(line)?;
+        let span = self.lines()?.get(line)?.clone();
+        self.edit(span, String::new())
+    }
+
+    /// Replaces the rule on a line with `rule`, keeping an end comment
+    /// and the line ending. Returns `None` if the line does not exist,
+    /// holds no rule, e.g. a comment, or already holds the rule in
+    /// canonical form.
+    pub fn replace(&mut self, line: usize, rule: &Rule) -> Option<TextEdit> {
+        let span = self.rule_span(line)?;
+        let new_text = rule.to_string();
+        if self.source[span.clone()] == new_text {
+            return None;
+        }
+        self.edit(span, new_text)
+    }
+}
+
+#[cfg(all(test, any(feature = "chardef", feature = "translation")))]
+mod tests {
+    use super::*;
+    use crate::parser::complete_rule;
+
+    const SOURCE: &str = concat!("# a comment\n",
+                                 "display  a    1 keep this\r\n",
+                                 "joinword to 235\n");
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn insert_test() {
+        let rule = complete_rule("digit 1 1").unwrap();
+        let mut editor = TableEditor::new(SOURCE);
+        assert_eq!(editor.insert(1, &rule), Some(TextEdit { range: 12..12, new_text: "digit 1 1\n".to_string() }));
+        assert_eq!(editor.insert(4, &rule), Some(TextEdit { range: 65..65, new_text: "digit 1 1\n".to_string() }));
+        assert_eq!(editor.insert(6, &rule), None);
+        assert_eq!(editor.source(),
+                   concat!("# a comment\n",
+                           "digit 1 1\n",
+                           "display  a    1 keep this\r\n",
+                           "joinword to 235\n",
+                           "digit 1 1\n"));
+        assert_eq!(editor.table().unwrap().1.lines().len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn remove_test() {
+        let mut editor = TableEditor::new(SOURCE);
+        assert_eq!(editor.remove(0), None);
+        assert_eq!(editor.remove(1), Some(TextEdit { range: 12..39, new_text: String::new() }));
+        assert_eq!(editor.remove(2), None);
+        assert_eq!(editor.source(), "# a comment\njoinword to 235\n");
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn patch_test() {
+        let mut editor = TableEditor::new(SOURCE);
+        assert_eq!(editor.patch("en.ctb"), "");
+        editor.replace(2, &complete_rule("joinword to 2356").unwrap());
+        assert_eq!(editor.patch("en.ctb"),
+                   concat!("--- a/en.ctb\n",
+                           "+++ b/en.ctb\n",
+                           "@@ -1,3 +1,3 @@\n",
+                           " # a comment\n",
+                           " display  a    1 keep this\r\n",
+                           "-joinword to 235\n",
+                           "+joinword to 2356\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn replace_test() {
+        let mut editor = TableEditor::new(SOURCE);
+        let rule = complete_rule("display a 12").unwrap();
+        assert_eq!(editor.replace(1, &rule), Some(TextEdit { range: 12..27, new_text: "display a 12".to_string() }));
+        assert_eq!(editor.replace(1, &rule), None);
+        assert_eq!(editor.replace(0, &rule), None);
+        assert_eq!(editor.replace(3, &rule), None);
+        assert_eq!(editor.source(),
+                   concat!("# a comment\n",
+                           "display a 12 keep this\r\n",
+                           "joinword to 235\n"));
+    }
+}
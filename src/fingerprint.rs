@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::interop::RuleSource;
+use crate::parser::{table, Line, Rule};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A content based identity of a rule.
+///
+/// The fingerprint is computed from the canonical form of the rule
+/// (see the `Display` impl of `Rule`) using 64 bit FNV-1a, so it does
+/// not depend on whitespace, comments or the Rust version it was
+/// computed with.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Fingerprint(pub u64);
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+fn fnv1a_update(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_update(FNV_OFFSET_BASIS, bytes)
+}
+
+pub fn fingerprint(rule: &Rule) -> Fingerprint {
+    Fingerprint(fnv1a(rule.to_string().as_bytes()))
+}
+
+/// A content based identity of a whole table.
+///
+/// The hash is computed from the canonical forms of the rules, one per
+/// line, so comments, empty lines and formatting do not change it. The
+/// rules are expected in include-resolved order, see `resolved_hash`.
+pub fn content_hash<'a, S: RuleSource<'a> + ?Sized>(source: &S) -> Fingerprint {
+    Fingerprint(source.rules().iter().fold(FNV_OFFSET_BASIS, |hash, rule| {
+        fnv1a_update(hash, format!("{}\n", rule).as_bytes())
+    }))
+}
+
+fn resolve<F>(file: &str, load: &mut F, visiting: &mut HashSet<String>, hash: u64) -> Option<u64>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    if !visiting.insert(file.to_string()) {
+        // an include cycle, which liblouis rejects as well
+        return None;
+    }
+    let source = load(file)?;
+    let (rest, lines) = table(&source).ok()?;
+    if !rest.is_empty() {
+        return None;
+    }
+    let mut hash = hash;
+    for line in &lines {
+        hash = match line {
+            Line::Rule { rule: Rule::Include { filename }, .. } => resolve(filename, load, visiting, hash)?,
+            Line::Rule { rule, .. } => fnv1a_update(hash, format!("{}\n", rule).as_bytes()),
+            _ => hash,
+        };
+    }
+    visiting.remove(file);
+    Some(hash)
+}
+
+/// The `content_hash` of the table `root` with every `include` rule
+/// replaced by the rules of the included file. `load` returns the
+/// source of a file. Returns `None` if a file cannot be loaded or
+/// parsed, or if the includes form a cycle.
+pub fn resolved_hash<F>(root: &str, mut load: F) -> Option<Fingerprint>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    resolve(root, &mut load, &mut HashSet::new(), FNV_OFFSET_BASIS).map(Fingerprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    use crate::parser::{rule_line, Line};
+
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn fingerprint_of(line: &str) -> Fingerprint {
+        match rule_line(line) {
+            Ok((_, Line::Rule { rule, .. })) => fingerprint(&rule),
+            _ => panic!("not a rule: {}", line),
+        }
+    }
+
+    #[test]
+    fn fnv1a_test() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn fingerprint_ignores_formatting_test() {
+        assert_eq!(fingerprint_of("joinword haha 123\n"),
+                   fingerprint_of("joinword \t haha   123   a comment\n"));
+        assert_eq!(fingerprint_of("nocross display haha 12\n"),
+                   fingerprint_of("nocross  display haha 21\n"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn fingerprint_differs_test() {
+        assert_ne!(fingerprint_of("joinword haha 123\n"),
+                   fingerprint_of("syllable haha 123\n"));
+        assert_ne!(fingerprint_of("joinword haha 123\n"),
+                   fingerprint_of("joinword haha 124\n"));
+        assert_ne!(fingerprint_of("display haha 12\n"),
+                   fingerprint_of("nocross display haha 12\n"));
+    }
+
+    #[test]
+    fn fingerprint_display_test() {
+        assert_eq!(Fingerprint(0xaf63dc4c8601ec8c).to_string(), "af63dc4c8601ec8c");
+        assert_eq!(Fingerprint(1).to_string(), "0000000000000001");
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn content_hash_test() {
+        let (_, a) = table("# a comment\ndisplay a 1\n\njoinword to 235 end\n").unwrap();
+        let (_, b) = table("display  a  1\njoinword to 235\n").unwrap();
+        let (_, c) = table("joinword to 235\ndisplay a 1\n").unwrap();
+        assert_eq!(content_hash(&a), content_hash(&b));
+        assert_ne!(content_hash(&a), content_hash(&c));
+        assert_eq!(content_hash(&Vec::new()), Fingerprint(FNV_OFFSET_BASIS));
+    }
+
+    #[test]
+    #[cfg(all(feature = "meta", feature = "chardef", feature = "translation"))]
+    fn resolved_hash_test() {
+        let load = |file: &str| match file {
+            "main.ctb" => Some("include chars.cti\njoinword to 235\n".to_string()),
+            "chars.cti" => Some("# the letters\ndisplay a 1\n".to_string()),
+            "cycle.ctb" => Some("include cycle.ctb\n".to_string()),
+            _ => None,
+        };
+        let (_, flat) = table("display a 1\njoinword to 235\n").unwrap();
+        assert_eq!(resolved_hash("main.ctb", load), Some(content_hash(&flat)));
+        assert_eq!(resolved_hash("cycle.ctb", load), None);
+        assert_eq!(resolved_hash("missing.ctb", load), None);
+    }
+}
@@ -0,0 +1,242 @@
+//! A standalone HTML page for reviewing a table.
+//!
+//! The page shows the metadata and the kind of the table, its
+//! diagnostics and the highlighted source. Dots operands show their
+//! braille cells on hover and included files link to their own pages,
+//! which are expected next to it as `<filename>.html`. Uses of class
+//! and swap names link to their definition if it is in the same
+//! table.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::classify::{classify, metadata};
+use crate::diagnostic::{diagnostics, Severity};
+use crate::lexer::{tokenize_line, TokenKind};
+use crate::navigation::{defined_name, definition_of, references_of, NameKind};
+use crate::opcode::OperandKind;
+use crate::parser::{dots, dots_to_unicode, Line};
+use crate::table::Table;
+
+const STYLE: &str = "body { font-family: sans-serif; }
+pre { line-height: 1.4; }
+.line { display: block; }
+.line:target { background: #ffc; }
+.number { color: #999; display: inline-block; width: 4em; text-align: right; margin-right: 1em; }
+.keyword { color: #00a; font-weight: bold; }
+.modifier { color: #a0a; }
+.string { color: #070; }
+.enumMember { color: #077; }
+.number-operand { color: #a50; }
+.comment { color: #777; font-style: italic; }
+.unknown { color: #c00; text-decoration: underline wavy; }
+.error { color: #c00; }
+.warning { color: #a50; }";
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn class_of(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Operand(OperandKind::Dots) | TokenKind::Operand(OperandKind::Number) => "number-operand",
+        kind => kind.semantic_token_type().unwrap_or("unknown"),
+    }
+}
+
+/// A part of a line that defines a name or refers to one, with the id
+/// of the definition.
+#[derive(Clone, PartialEq, Debug)]
+enum Mark {
+    Definition(String),
+    Reference(String),
+}
+
+/// A word of a line starting at `offset`, with the marks in it.
+fn marked(word: &str, offset: usize, marks: &[(Range<usize>, Mark)]) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for (span, mark) in marks.iter().filter(|(span, _)| offset <= span.start && span.end <= offset + word.len()) {
+        let (start, end) = (span.start - offset, span.end - offset);
+        html.push_str(&escape(&word[last..start]));
+        match mark {
+            Mark::Definition(id) => write!(html, "<span id=\"{}\">{}</span>", escape(id), escape(&word[start..end])),
+            Mark::Reference(id) => write!(html, "<a href=\"#{}\">{}</a>", escape(id), escape(&word[start..end])),
+        }
+        .unwrap();
+        last = end;
+    }
+    html.push_str(&escape(&word[last..]));
+    html
+}
+
+/// A highlighted line of the source, with `marks` sorted by their
+/// byte offset in the line.
+fn highlight(text: &str, marks: &[(Range<usize>, Mark)]) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for token in tokenize_line(text) {
+        let word = &text[token.span.clone()];
+        html.push_str(&escape(&text[last..token.span.start]));
+        match token.kind {
+            TokenKind::Operand(OperandKind::Dots) => {
+                let title = match dots(word) {
+                    Ok(("", parsed)) => dots_to_unicode(&parsed),
+                    _ => None,
+                };
+                match title {
+                    Some(cells) => write!(html, "<span class=\"{}\" title=\"{}\">{}</span>", class_of(token.kind), cells, escape(word)),
+                    None => write!(html, "<span class=\"{}\">{}</span>", class_of(token.kind), escape(word)),
+                }
+            }
+            TokenKind::Operand(OperandKind::Filename) => {
+                write!(html, "<a class=\"string\" href=\"{}.html\">{}</a>", escape(word), escape(word))
+            }
+            kind => write!(html, "<span class=\"{}\">{}</span>", class_of(kind), marked(word, token.span.start, marks)),
+        }
+        .unwrap();
+        last = token.span.end;
+    }
+    html.push_str(&escape(&text[last..]));
+    html
+}
+
+/// The marks of the class, swap and emphasis class names defined in
+/// `table`, per line.
+/// A name that is defined more than once links to its first
+/// definition.
+fn name_marks(name: &str, table: &Table) -> Vec<Vec<(Range<usize>, Mark)>> {
+    let files = [(name, table)];
+    let mut marks: Vec<Vec<(Range<usize>, Mark)>> = table.lines().iter().map(|_| Vec::new()).collect();
+    let mut seen = Vec::new();
+    for line in table.lines() {
+        let Line::Rule { rule, .. } = line else { continue };
+        let Some((defined, kind)) = defined_name(rule) else { continue };
+        // a name defined both as a class and as a swap name links to
+        // whichever comes first
+        if seen.contains(&defined) {
+            continue;
+        }
+        seen.push(defined);
+        let id = match kind {
+            NameKind::Class => format!("class-{}", defined),
+            NameKind::Swap => format!("swap-{}", defined),
+            NameKind::Emphasis => format!("emphclass-{}", defined),
+        };
+        let definitions = definition_of(&files, defined, kind).into_iter().take(1).map(|location| (location, Mark::Definition(id.clone())));
+        let references = references_of(&files, defined, kind).into_iter().map(|location| (location, Mark::Reference(id.clone())));
+        for (location, mark) in definitions.chain(references) {
+            let start = table.span(location.line).map_or(0, |span| span.start);
+            marks[location.line].push((location.span.start - start..location.span.end - start, mark));
+        }
+    }
+    for line in &mut marks {
+        line.sort_by_key(|(span, _)| span.start);
+    }
+    marks
+}
+
+/// Renders the report for the table `name` with the given source.
+/// Lines that do not parse are reported as diagnostics, the rest of
+/// the table is rendered as usual.
+pub fn report(name: &str, source: &str) -> String {
+    let (_, table) = Table::parse(source).expect("invalid lines are kept as Line::Invalid");
+    let lines = table.lines();
+    let classification = classify(lines);
+    let index = table.line_index();
+    let marks = name_marks(name, &table);
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>", escape(name), STYLE).unwrap();
+    writeln!(html, "<h1>{}</h1>", escape(name)).unwrap();
+
+    writeln!(html, "<h2>Metadata</h2>\n<dl>").unwrap();
+    for (key, value) in metadata(lines) {
+        writeln!(html, "<dt>{}</dt><dd>{}</dd>", escape(key), escape(value)).unwrap();
+    }
+    writeln!(html, "<dt>kind</dt><dd>{:?}</dd>", classification.kind).unwrap();
+    writeln!(html, "<dt>contraction</dt><dd>{:?}</dd>\n</dl>", classification.contraction).unwrap();
+
+    let diagnostics = diagnostics(source);
+    writeln!(html, "<h2>Diagnostics</h2>").unwrap();
+    if diagnostics.is_empty() {
+        writeln!(html, "<p>None</p>").unwrap();
+    } else {
+        writeln!(html, "<ul>").unwrap();
+        for diagnostic in &diagnostics {
+            let line = index.line_column(diagnostic.span.start).map_or(0, |position| position.line) + 1;
+            let class = match diagnostic.code.severity() {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(html, "<li class=\"{}\"><a href=\"#L{}\">line {}</a>: {} {}</li>",
+                     class, line, line, diagnostic.code.code(), escape(&diagnostic.message())).unwrap();
+        }
+        writeln!(html, "</ul>").unwrap();
+    }
+
+    writeln!(html, "<h2>Source</h2>\n<pre>").unwrap();
+    for (number, text) in source.lines().enumerate() {
+        let marks = marks.get(number).map_or(&[][..], Vec::as_slice);
+        writeln!(html, "<span class=\"line\" id=\"L{}\"><span class=\"number\">{}</span>{}</span>",
+                 number + 1, number + 1, highlight(text, marks)).unwrap();
+    }
+    writeln!(html, "</pre>\n</body>\n</html>").unwrap();
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_test() {
+        assert_eq!(highlight("nocross display < 1 a comment", &[]),
+                   concat!("<span class=\"modifier\">nocross</span> ",
+                           "<span class=\"keyword\">display</span> ",
+                           "<span class=\"string\">&lt;</span> ",
+                           "<span class=\"number-operand\" title=\"⠁\">1</span> ",
+                           "<span class=\"comment\">a comment</span>"));
+        assert_eq!(highlight("include chardefs.cti", &[]),
+                   "<span class=\"keyword\">include</span> <a class=\"string\" href=\"chardefs.cti.html\">chardefs.cti</a>");
+    }
+
+    #[test]
+    fn report_test() {
+        let html = report("de.ctb", "#+language: de\ninclude chardefs.cti\nfoo a 1\n");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<dt>language</dt><dd>de</dd>"));
+        assert!(html.contains("<li class=\"error\"><a href=\"#L3\">line 3</a>: E001 unknown opcode `foo`</li>"));
+        assert!(html.contains("<span class=\"line\" id=\"L2\"><span class=\"number\">2</span><span class=\"keyword\">include</span>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn report_invalid_line_test() {
+        let html = report("de.ctb", "display b\n#+language: de\ndisplay a 1\n");
+        assert!(html.contains("<dt>language</dt><dd>de</dd>"));
+        assert!(html.contains("<dt>kind</dt><dd>Display</dd>"));
+        assert!(html.contains("<a href=\"#L1\">line 1</a>: E002"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "multipass"))]
+    fn class_links_test() {
+        let html = report("de.ctb", "attribute vowels aeiou\ncontext %vowels[%vowels] %dropped\nswapcc dropped a b\n");
+        assert!(html.contains("<span class=\"variable\"><span id=\"class-vowels\">vowels</span></span>"));
+        assert!(html.contains("%<a href=\"#class-vowels\">vowels</a>[%<a href=\"#class-vowels\">vowels</a>]"));
+        assert!(html.contains("%<a href=\"#swap-dropped\">dropped</a>"));
+        assert!(html.contains("<span id=\"swap-dropped\">dropped</span>"));
+    }
+}
@@ -0,0 +1,185 @@
+//! The include dependencies of tables.
+//!
+//! `scan_includes` finds the `include` rules of a table with a plain
+//! text scan, without parsing the other rules, so that build systems
+//! can compute the dependencies of many tables quickly. From these,
+//! `IncludeGraph` builds the include graph of a set of tables, which
+//! can be exported in the DOT format of GraphViz.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct IncludeRef<'a> {
+    pub filename: &'a str,
+    /// The zero based index of the line.
+    pub line: usize,
+    /// The byte span of the filename in the source.
+    pub span: Range<usize>,
+}
+
+/// Finds the `include` rules of a table.
+pub fn scan_includes(source: &str) -> Vec<IncludeRef<'_>> {
+    let mut includes = Vec::new();
+    let mut offset = 0;
+    for (line, text) in source.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += text.len();
+        let Some(rest) = text.trim_start().strip_prefix("include") else {
+            continue;
+        };
+        let filename = rest.trim_start();
+        if filename.len() == rest.len() {
+            // e.g. `includefoo`
+            continue;
+        }
+        let filename = filename.split_whitespace().next().unwrap_or("");
+        if filename.is_empty() {
+            continue;
+        }
+        let filename_start = start + (text.len() - rest.len()) + (rest.len() - rest.trim_start().len());
+        includes.push(IncludeRef { filename, line, span: filename_start..filename_start + filename.len() });
+    }
+    includes
+}
+
+/// The include graph of one or more tables. Nodes are file names as
+/// written in the `include` rules.
+#[derive(PartialEq, Debug, Default)]
+pub struct IncludeGraph {
+    pub files: Vec<String>,
+    /// The edges from including to included file, as indices into
+    /// `files`, in the order of the `include` rules.
+    pub edges: Vec<(usize, usize)>,
+    /// The indices of the files that could not be loaded.
+    pub missing: Vec<usize>,
+    index: HashMap<String, usize>,
+}
+
+impl IncludeGraph {
+    /// Builds the include graph of the tables in `roots` and everything
+    /// they include. `load` returns the source of a file, or `None` if
+    /// it cannot be found. Every file is loaded at most once, so cycles
+    /// are fine.
+    pub fn build<F>(roots: &[&str], mut load: F) -> IncludeGraph
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut graph = IncludeGraph::default();
+        let mut pending: Vec<usize> = roots.iter().map(|root| graph.node(root)).collect();
+        pending.reverse();
+        let mut loaded = vec![false; graph.files.len()];
+        while let Some(file) = pending.pop() {
+            loaded.resize(graph.files.len(), false);
+            if loaded[file] {
+                continue;
+            }
+            loaded[file] = true;
+            let Some(source) = load(&graph.files[file]) else {
+                graph.missing.push(file);
+                continue;
+            };
+            let included: Vec<usize> =
+                scan_includes(&source).iter().map(|include| graph.node(include.filename)).collect();
+            for target in included.iter().rev() {
+                pending.push(*target);
+            }
+            graph.edges.extend(included.into_iter().map(|target| (file, target)));
+        }
+        graph
+    }
+
+    fn node(&mut self, file: &str) -> usize {
+        if let Some(index) = self.index.get(file) {
+            return *index;
+        }
+        self.files.push(file.to_string());
+        self.index.insert(file.to_string(), self.files.len() - 1);
+        self.files.len() - 1
+    }
+
+    /// The files directly included by `file`.
+    pub fn includes(&self, file: &str) -> Vec<&str> {
+        match self.index.get(file) {
+            Some(from) => self.edges.iter().filter(|(f, _)| f == from).map(|(_, to)| self.files[*to].as_str()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The graph in the DOT format of GraphViz. Missing files are drawn
+    /// dashed.
+    pub fn to_dot(&self) -> String {
+        let quote = |file: &str| format!("\"{}\"", file.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = String::from("digraph includes {\n");
+        for (index, file) in self.files.iter().enumerate() {
+            if self.missing.contains(&index) {
+                dot.push_str(&format!("    {} [style=dashed];\n", quote(file)));
+            } else {
+                dot.push_str(&format!("    {};\n", quote(file)));
+            }
+        }
+        for (from, to) in &self.edges {
+            dot.push_str(&format!("    {} -> {};\n", quote(&self.files[*from]), quote(&self.files[*to])));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_includes_test() {
+        let source = concat!("# include nothing.tbl\n",
+                             "include chardefs.cti\n",
+                             "display a 1\n",
+                             "  include\tlatinLetterDef6Dots.uti a comment\r\n",
+                             "includes foo\n",
+                             "include\n",
+                             "include last.cti");
+        assert_eq!(scan_includes(source),
+                   vec![IncludeRef { filename: "chardefs.cti", line: 1, span: 30..42 },
+                        IncludeRef { filename: "latinLetterDef6Dots.uti", line: 3, span: 65..88 },
+                        IncludeRef { filename: "last.cti", line: 6, span: 129..137 }]);
+        assert_eq!(&source[65..88], "latinLetterDef6Dots.uti");
+        assert_eq!(&source[129..137], "last.cti");
+    }
+
+    fn load(file: &str) -> Option<String> {
+        match file {
+            "en-us-g2.ctb" => Some("include en-chardefs.cti\ninclude en-us-g1.ctb\n".to_string()),
+            "en-us-g1.ctb" => Some("include en-chardefs.cti\ninclude braille-patterns.cti\n".to_string()),
+            "en-chardefs.cti" => Some("include en-us-g2.ctb\ndisplay a 1\n".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn include_graph_test() {
+        let graph = IncludeGraph::build(&["en-us-g2.ctb"], load);
+        assert_eq!(graph.files, vec!["en-us-g2.ctb", "en-chardefs.cti", "en-us-g1.ctb", "braille-patterns.cti"]);
+        assert_eq!(graph.edges, vec![(0, 1), (0, 2), (1, 0), (2, 1), (2, 3)]);
+        assert_eq!(graph.missing, vec![3]);
+        assert_eq!(graph.includes("en-us-g1.ctb"), vec!["en-chardefs.cti", "braille-patterns.cti"]);
+        assert_eq!(graph.includes("foo.ctb"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn to_dot_test() {
+        let graph = IncludeGraph::build(&["en-us-g1.ctb"], load);
+        assert_eq!(graph.to_dot(),
+                   concat!("digraph includes {\n",
+                           "    \"en-us-g1.ctb\";\n",
+                           "    \"en-chardefs.cti\";\n",
+                           "    \"braille-patterns.cti\" [style=dashed];\n",
+                           "    \"en-us-g2.ctb\";\n",
+                           "    \"en-us-g1.ctb\" -> \"en-chardefs.cti\";\n",
+                           "    \"en-us-g1.ctb\" -> \"braille-patterns.cti\";\n",
+                           "    \"en-chardefs.cti\" -> \"en-us-g2.ctb\";\n",
+                           "    \"en-us-g2.ctb\" -> \"en-chardefs.cti\";\n",
+                           "    \"en-us-g2.ctb\" -> \"en-us-g1.ctb\";\n",
+                           "}\n"));
+    }
+}
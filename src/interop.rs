@@ -0,0 +1,81 @@
+//! Traits for translator crates that consume parsed tables.
+//!
+//! A translator only needs the rules and the character definitions of
+//! a table. Depending on these traits rather than on `Vec<Line>`
+//! allows it to be fed from other backends, e.g. a precompiled table.
+
+use crate::parser::{BrailleChars, Line, Rule};
+
+pub trait RuleSource<'a> {
+    /// All rules in table order.
+    fn rules(&self) -> Vec<&Rule<'a>>;
+}
+
+pub trait CharDefinitionSource {
+    /// All character definitions in table order.
+    fn char_definitions(&self) -> Vec<(char, &BrailleChars)>;
+}
+
+impl<'a> RuleSource<'a> for [Line<'a>] {
+    fn rules(&self) -> Vec<&Rule<'a>> {
+        self.iter()
+            .filter_map(|line| match line {
+                Line::Rule { rule, .. } => Some(rule),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl CharDefinitionSource for [Line<'_>] {
+    fn char_definitions(&self) -> Vec<(char, &BrailleChars)> {
+        self.rules()
+            .into_iter()
+            .flat_map(|rule| rule.char_definitions())
+            .collect()
+    }
+}
+
+impl<'a> RuleSource<'a> for Vec<Line<'a>> {
+    fn rules(&self) -> Vec<&Rule<'a>> {
+        self.as_slice().rules()
+    }
+}
+
+impl CharDefinitionSource for Vec<Line<'_>> {
+    fn char_definitions(&self) -> Vec<(char, &BrailleChars)> {
+        self.as_slice().char_definitions()
+    }
+}
+
+#[cfg(all(test, feature = "translation"))]
+mod tests {
+    use super::*;
+    use crate::parser::{table, BrailleDot};
+    #[cfg(feature = "chardef")]
+    use enumset::enum_set;
+
+    #[test]
+    fn rules_test() {
+        let (_, lines) = table(concat!("# a comment\n",
+                                       "\n",
+                                       "joinword haha 123\n")).unwrap();
+        assert_eq!(lines.rules(),
+                   vec![&Rule::Joinword { word: "haha",
+                                          dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] }]);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn char_definitions_test() {
+        let (_, lines) = table(concat!("space . 0\n",
+                                       "joinword haha 123\n",
+                                       "digit 1 1\n",
+                                       "uplow Bb 12\n")).unwrap();
+        assert_eq!(lines.char_definitions(),
+                   vec![('.', &vec![enum_set!(BrailleDot::DOT0)]),
+                        ('1', &vec![enum_set!(BrailleDot::DOT1)]),
+                        ('B', &vec![BrailleDot::DOT1 | BrailleDot::DOT2]),
+                        ('b', &vec![BrailleDot::DOT1 | BrailleDot::DOT2])]);
+    }
+}
@@ -0,0 +1,170 @@
+//! A line based lexer for editors.
+//!
+//! Lines of a table are independent, so a changed line can be
+//! re-tokenized without parsing the rest of the table. The lexer only
+//! classifies words by their position on the line and does not check
+//! them, so it also works on lines the parser rejects.
+
+use std::ops::Range;
+
+use crate::opcode::{OperandKind, Opcode};
+use crate::parser::Prefix;
+
+use enumset::EnumSet;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TokenKind {
+    Prefix,
+    Opcode(Opcode),
+    Operand(OperandKind),
+    Comment,
+    /// A word that is not a known opcode and everything after it.
+    Unknown,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// The byte span of the token within the line.
+    pub span: Range<usize>,
+}
+
+impl TokenKind {
+    /// The LSP semantic token type of the token, if any.
+    pub fn semantic_token_type(&self) -> Option<&'static str> {
+        match self {
+            TokenKind::Prefix => Some("modifier"),
+            TokenKind::Opcode(_) => Some("keyword"),
+            TokenKind::Operand(OperandKind::Dots) | TokenKind::Operand(OperandKind::Number) => Some("number"),
+            TokenKind::Operand(OperandKind::Position) => Some("enumMember"),
+            TokenKind::Operand(OperandKind::Name) => Some("variable"),
+            TokenKind::Operand(_) => Some("string"),
+            TokenKind::Comment => Some("comment"),
+            TokenKind::Unknown => None,
+        }
+    }
+
+    /// The TextMate scope of the token.
+    pub fn textmate_scope(&self) -> &'static str {
+        match self {
+            TokenKind::Prefix => "storage.modifier.liblouis",
+            TokenKind::Opcode(_) => "keyword.other.opcode.liblouis",
+            TokenKind::Operand(OperandKind::Dots) => "constant.numeric.dots.liblouis",
+            TokenKind::Operand(OperandKind::Number) => "constant.numeric.liblouis",
+            TokenKind::Operand(OperandKind::Position) => "constant.language.liblouis",
+            TokenKind::Operand(OperandKind::Filename) => "string.unquoted.filename.liblouis",
+            TokenKind::Operand(OperandKind::Name) => "variable.other.name.liblouis",
+            TokenKind::Operand(_) => "string.unquoted.liblouis",
+            TokenKind::Comment => "comment.line.liblouis",
+            TokenKind::Unknown => "invalid.illegal.liblouis",
+        }
+    }
+}
+
+/// Splits `text` into whitespace separated words with their byte
+/// offsets.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                words.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        words.push(s..text.len());
+    }
+    words
+}
+
+fn is_prefix(word: &str) -> bool {
+    EnumSet::<Prefix>::all().iter().any(|prefix| prefix.keyword() == word)
+}
+
+/// Tokenizes a single line of a table. A line ending, if present, is
+/// ignored. Whitespace between tokens is not part of any token. Like
+/// in the parser, only a `#` at the start of the line starts a comment
+/// line.
+pub fn tokenize_line(line: &str) -> Vec<Token> {
+    let line = line.trim_end_matches(['\r', '\n']);
+    let mut words = words(line).into_iter().peekable();
+    let mut tokens = Vec::new();
+    if line.starts_with('#') {
+        if let Some(word) = words.next() {
+            tokens.push(Token { kind: TokenKind::Comment, span: word.start..line.len() });
+        }
+        return tokens;
+    }
+    while let Some(word) = words.next_if(|word| is_prefix(&line[word.clone()])) {
+        tokens.push(Token { kind: TokenKind::Prefix, span: word });
+    }
+    let opcode = match words.next() {
+        Some(word) => match Opcode::from_keyword(&line[word.clone()]) {
+            Some(opcode) => {
+                tokens.push(Token { kind: TokenKind::Opcode(opcode), span: word });
+                opcode
+            }
+            None => {
+                tokens.push(Token { kind: TokenKind::Unknown, span: word.start..line.len() });
+                return tokens;
+            }
+        },
+        None => return tokens,
+    };
+    for kind in opcode.operands() {
+        match words.next() {
+            Some(word) => tokens.push(Token { kind: TokenKind::Operand(*kind), span: word }),
+            None => return tokens,
+        }
+    }
+    if let Some(word) = words.next() {
+        tokens.push(Token { kind: TokenKind::Comment, span: word.start..line.len() });
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_rule_test() {
+        assert_eq!(tokenize_line("noback nocross  display a 1 a comment\n"),
+                   vec![Token { kind: TokenKind::Prefix, span: 0..6 },
+                        Token { kind: TokenKind::Prefix, span: 7..14 },
+                        Token { kind: TokenKind::Opcode(Opcode::Display), span: 16..23 },
+                        Token { kind: TokenKind::Operand(OperandKind::Chars), span: 24..25 },
+                        Token { kind: TokenKind::Operand(OperandKind::Dots), span: 26..27 },
+                        Token { kind: TokenKind::Comment, span: 28..37 }]);
+    }
+
+    #[test]
+    fn tokenize_incomplete_test() {
+        assert_eq!(tokenize_line("endcapsphrase after"),
+                   vec![Token { kind: TokenKind::Opcode(Opcode::Endcapsphrase), span: 0..13 },
+                        Token { kind: TokenKind::Operand(OperandKind::Position), span: 14..19 }]);
+        assert_eq!(tokenize_line("foo bar 12"),
+                   vec![Token { kind: TokenKind::Unknown, span: 0..10 }]);
+        assert_eq!(tokenize_line("   \r\n"), vec![]);
+    }
+
+    #[test]
+    fn tokenize_comment_test() {
+        assert_eq!(tokenize_line("# display a 1\n"),
+                   vec![Token { kind: TokenKind::Comment, span: 0..13 }]);
+        assert_eq!(tokenize_line("  # display a 1\n"),
+                   vec![Token { kind: TokenKind::Unknown, span: 2..15 }]);
+    }
+
+    #[test]
+    fn scopes_test() {
+        assert_eq!(TokenKind::Opcode(Opcode::Display).semantic_token_type(), Some("keyword"));
+        assert_eq!(TokenKind::Operand(OperandKind::Dots).textmate_scope(), "constant.numeric.dots.liblouis");
+        assert_eq!(TokenKind::Unknown.semantic_token_type(), None);
+    }
+}
@@ -1,4 +1,33 @@
+#[macro_use]
+mod macros;
+
 pub mod parser;
+pub mod fingerprint;
+pub mod interop;
+pub mod display_map;
+pub mod preview;
+pub mod opcode;
+pub mod complete;
+pub mod lexer;
+pub mod line_index;
+pub mod table;
+pub mod edit;
+pub mod editor;
+pub mod diff;
+pub mod diagnostic;
+pub mod classify;
+pub mod coverage;
+pub mod redundancy;
+pub mod script;
+pub mod include;
+pub mod navigation;
+pub mod html;
+#[cfg(feature = "liblouis")]
+pub mod liblouis;
+pub mod code_action;
+pub mod roundtrip;
+#[cfg(any(test, feature = "test-util"))]
+pub mod snapshot;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,72 @@
+//! Cross-checks the parser against the table compiler of liblouis,
+//! enabled with the `liblouis` feature.
+//!
+//! The feature links against the C library, which has to be
+//! installed. liblouis is the reference for which tables are valid, so
+//! a table that one side accepts and the other rejects points at a gap
+//! or a bug in this parser.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+
+use crate::parser::table;
+
+#[link(name = "louis")]
+extern "C" {
+    fn lou_checkTable(table_list: *const c_char) -> c_int;
+    fn lou_free();
+}
+
+/// Whether liblouis compiles the table files in `table_list`, a comma
+/// separated list of paths as accepted by `lou_checkTable`.
+pub fn liblouis_accepts(table_list: &str) -> bool {
+    let Ok(table_list) = CString::new(table_list) else {
+        return false;
+    };
+    // SAFETY: the pointer is valid and NUL terminated for the duration
+    // of the call, and liblouis does not keep it.
+    let result = unsafe { lou_checkTable(table_list.as_ptr()) };
+    // SAFETY: frees the tables compiled by liblouis, none of which are
+    // referenced by this crate.
+    unsafe { lou_free() };
+    result != 0
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Verdict {
+    pub liblouis: bool,
+    /// Whether this parser accepts the whole file.
+    pub parser: bool,
+}
+
+impl Verdict {
+    pub fn diverges(&self) -> bool {
+        self.liblouis != self.parser
+    }
+}
+
+/// Feeds the table file at `path` to liblouis and to this parser.
+///
+/// Includes are resolved by liblouis but not by this parser, which
+/// only looks at the file itself.
+pub fn cross_check(path: &Path) -> std::io::Result<Verdict> {
+    let source = std::fs::read_to_string(path)?;
+    let parser = matches!(table(&source), Ok(("", _)));
+    Ok(Verdict { liblouis: liblouis_accepts(&path.to_string_lossy()), parser })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_check_test() {
+        let path = std::env::temp_dir().join(format!("liblouis-nom-cross-check-{}.ctb", std::process::id()));
+        std::fs::write(&path, "punctuation . 256\nfoo a 1\n").unwrap();
+        let verdict = cross_check(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(verdict, Verdict { liblouis: false, parser: false });
+        assert!(!verdict.diverges());
+    }
+}
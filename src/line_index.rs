@@ -0,0 +1,125 @@
+//! Conversion between byte offsets and line/column positions.
+//!
+//! Spans in this crate are byte offsets. Editors count columns
+//! differently: LSP uses UTF-16 code units, others use Unicode scalar
+//! values. For scripts outside the ASCII range, e.g. in Tamil tables,
+//! these differ from byte columns.
+
+use std::sync::OnceLock;
+
+/// A zero based line and column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Maps between byte offsets and line/column positions of a source.
+/// The start offsets of the lines are only collected on the first
+/// lookup, so an index that is never used costs nothing.
+#[derive(PartialEq, Debug)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    starts: OnceLock<Vec<usize>>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> LineIndex<'a> {
+        LineIndex { source, starts: OnceLock::new() }
+    }
+
+    fn starts(&self) -> &[usize] {
+        self.starts.get_or_init(|| {
+            std::iter::once(0)
+                .chain(self.source.match_indices('\n').map(|(i, _)| i + 1))
+                .collect()
+        })
+    }
+
+    fn line_of(&self, offset: usize) -> Option<usize> {
+        if offset > self.source.len() {
+            return None;
+        }
+        Some(self.starts().partition_point(|start| *start <= offset) - 1)
+    }
+
+    /// The text of the line up to `offset`, or `None` if `offset` is
+    /// out of bounds or not on a character boundary.
+    fn prefix(&self, offset: usize) -> Option<(usize, &'a str)> {
+        let line = self.line_of(offset)?;
+        self.source.get(self.starts()[line]..offset).map(|prefix| (line, prefix))
+    }
+
+    /// The line and byte column of a byte offset.
+    pub fn line_column(&self, offset: usize) -> Option<LineColumn> {
+        self.prefix(offset).map(|(line, prefix)| LineColumn { line, column: prefix.len() })
+    }
+
+    /// The line and UTF-16 column of a byte offset, as used by LSP.
+    pub fn utf16_line_column(&self, offset: usize) -> Option<LineColumn> {
+        self.prefix(offset).map(|(line, prefix)| LineColumn { line, column: prefix.encode_utf16().count() })
+    }
+
+    /// The line and Unicode scalar column of a byte offset.
+    pub fn char_line_column(&self, offset: usize) -> Option<LineColumn> {
+        self.prefix(offset).map(|(line, prefix)| LineColumn { line, column: prefix.chars().count() })
+    }
+
+    /// The byte offset of a line and UTF-16 column.
+    pub fn offset_of_utf16(&self, position: LineColumn) -> Option<usize> {
+        let starts = self.starts();
+        let start = *starts.get(position.line)?;
+        let end = starts.get(position.line + 1).copied().unwrap_or(self.source.len());
+        let mut column = 0;
+        for (i, c) in self.source[start..end].char_indices() {
+            if column == position.column {
+                return Some(start + i);
+            }
+            column += c.len_utf16();
+        }
+        (column == position.column).then_some(end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "display a 1\nlargesign அஇ 123\n𝐀 x";
+
+    #[test]
+    fn line_column_test() {
+        let index = LineIndex::new(SOURCE);
+        assert_eq!(index.starts.get(), None);
+        assert_eq!(index.line_column(0), Some(LineColumn { line: 0, column: 0 }));
+        assert_eq!(index.line_column(11), Some(LineColumn { line: 0, column: 11 }));
+        assert_eq!(index.line_column(12), Some(LineColumn { line: 1, column: 0 }));
+        assert_eq!(index.line_column(SOURCE.len()), Some(LineColumn { line: 2, column: 6 }));
+        assert_eq!(index.line_column(SOURCE.len() + 1), None);
+        assert_eq!(index.starts.get(), Some(&vec![0, 12, 33]));
+    }
+
+    #[test]
+    fn utf16_and_char_column_test() {
+        let index = LineIndex::new(SOURCE);
+        // the offset of "123"
+        let offset = 12 + "largesign அஇ ".len();
+        assert_eq!(index.line_column(offset), Some(LineColumn { line: 1, column: 17 }));
+        assert_eq!(index.utf16_line_column(offset), Some(LineColumn { line: 1, column: 13 }));
+        assert_eq!(index.char_line_column(offset), Some(LineColumn { line: 1, column: 13 }));
+        // the offset of "x"
+        let offset = SOURCE.len() - 1;
+        assert_eq!(index.utf16_line_column(offset), Some(LineColumn { line: 2, column: 3 }));
+        assert_eq!(index.char_line_column(offset), Some(LineColumn { line: 2, column: 2 }));
+        assert_eq!(index.utf16_line_column(offset - 1 - 1), None);
+    }
+
+    #[test]
+    fn offset_of_utf16_test() {
+        let index = LineIndex::new(SOURCE);
+        assert_eq!(index.offset_of_utf16(LineColumn { line: 1, column: 13 }), Some(12 + "largesign அஇ ".len()));
+        assert_eq!(index.offset_of_utf16(LineColumn { line: 2, column: 3 }), Some(SOURCE.len() - 1));
+        assert_eq!(index.offset_of_utf16(LineColumn { line: 2, column: 1 }), None);
+        assert_eq!(index.offset_of_utf16(LineColumn { line: 3, column: 0 }), None);
+    }
+}
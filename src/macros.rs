@@ -0,0 +1,106 @@
+/// Builds `BrailleChars` from one string of dot numbers per cell.
+///
+/// ```
+/// use liblouis_nom::dots;
+/// use liblouis_nom::parser::BrailleDot;
+///
+/// assert_eq!(dots!["123", "45"],
+///            vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3,
+///                 BrailleDot::DOT4 | BrailleDot::DOT5]);
+/// ```
+///
+/// Panics if a cell contains anything but the dot numbers `0` to `9`
+/// and `a` to `f`.
+#[macro_export]
+macro_rules! dots {
+    ($($cell:literal),* $(,)?) => {
+        vec![$($crate::parser::chars_to_dots($cell)),*]
+    };
+}
+
+/// Builds a `Rule` from its table syntax, with character and dots
+/// operands given as literals separated by `=>`.
+///
+/// ```
+/// use liblouis_nom::{dots, rule};
+/// use liblouis_nom::parser::{Prefix, Rule};
+///
+/// # #[cfg(all(feature = "chardef", feature = "emphasis"))] {
+/// assert_eq!(rule!(nocross display "a" => "1"),
+///            Rule::Display { chars: "a", dots: dots!["1"], prefixes: Prefix::Nocross.into() });
+/// assert_eq!(rule!(begcaps "6-6"), Rule::Begcaps { dots: dots!["6", "6"] });
+/// # }
+/// ```
+///
+/// The rule text is assembled at compile time, so the resulting rule
+/// borrows from a `'static` string. The opcode is checked at compile
+/// time as well: the first word after the prefixes has to be the
+/// keyword of an opcode, otherwise the macro does not compile.
+///
+/// ```compile_fail
+/// use liblouis_nom::rule;
+///
+/// let _ = rule!(nocross dispaly "a" => "1");
+/// ```
+///
+/// Everything else is only checked when the macro is evaluated, as
+/// macros cannot run the parser: the macro panics if the operands are
+/// invalid for the opcode, or if the family of the opcode is not
+/// enabled, see `opcode::Category`.
+#[macro_export]
+macro_rules! rule {
+    (@opcode noback $($word:ident)*) => { $crate::rule!(@opcode $($word)*) };
+    (@opcode nofor $($word:ident)*) => { $crate::rule!(@opcode $($word)*) };
+    (@opcode nocross $($word:ident)*) => { $crate::rule!(@opcode $($word)*) };
+    (@opcode $opcode:ident $($word:ident)*) => {
+        const _: () = assert!($crate::opcode::Opcode::is_keyword(stringify!($opcode)),
+                              concat!("unknown opcode ", stringify!($opcode)));
+    };
+    ($($word:ident)+ $($operand:literal)=>*) => {{
+        $crate::rule!(@opcode $($word)+);
+        const SOURCE: &str = concat!($(stringify!($word), " ",)+ $($operand, " ",)*);
+        match $crate::parser::complete_rule(SOURCE) {
+            Ok(rule) => rule,
+            Err(e) => panic!("invalid rule {:?}: {:?}", SOURCE, e),
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{dots, BrailleChars, BrailleDot};
+    #[cfg(all(feature = "meta", feature = "chardef", feature = "translation", feature = "emphasis"))]
+    use crate::parser::{Position, Prefix, Rule};
+
+    #[test]
+    fn dots_macro_test() {
+        assert_eq!(dots!["1"], vec![enumset::enum_set!(BrailleDot::DOT1)]);
+        assert_eq!(dots!["21", "3f",], dots("12-3f").unwrap().1);
+        let empty: BrailleChars = dots![];
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dots_macro_invalid_test() {
+        let _ = dots!["12x"];
+    }
+
+    #[test]
+    #[cfg(all(feature = "meta", feature = "chardef", feature = "translation", feature = "emphasis"))]
+    fn rule_macro_test() {
+        assert_eq!(rule!(joinword "அஇ" => "123"), Rule::Joinword { word: "அஇ", dots: dots!["123"] });
+        assert_eq!(rule!(endcapsphrase after "45-45"),
+                   Rule::Endcapsphrase { dots: dots!["45", "45"], position: Position::After });
+        assert_eq!(rule!(noback nocross display "a" => "1"),
+                   Rule::Display { chars: "a", dots: dots!["1"], prefixes: Prefix::Noback | Prefix::Nocross });
+        assert_eq!(rule!(include "braille-patterns.cti"), Rule::Include { filename: "braille-patterns.cti" });
+        assert_eq!(rule!(capsnocont), Rule::Capsnocont);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid rule")]
+    fn rule_macro_invalid_test() {
+        let _ = rule!(joinword "haha");
+    }
+}
@@ -0,0 +1,289 @@
+//! Go-to-definition and find-references for named entities.
+//!
+//! Classes are defined by `attribute` and the deprecated `class`, swap
+//! names by `swapcd`, `swapdd` and `swapcc`. Multipass tests and
+//! actions and the patterns of `match` rules refer to either as
+//! `%name`, `base` refers to a class by its plain name. Emphasis
+//! classes are defined by `emphclass` and referred to by the first
+//! operand of the mode opcodes `modeletter` and `begmodeword`.
+//!
+//! Like `redundancy::duplicates`, the functions take the files of a
+//! resolved include closure, so that a name can be followed into the
+//! file that defines it, and `rename` can edit every file that uses
+//! it.
+
+use std::ops::Range;
+
+use crate::edit::TextEdit;
+use crate::opcode::OperandKind;
+use crate::parser::{Line, Rule};
+use crate::table::Table;
+
+/// What a name is defined as.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NameKind {
+    /// A class of characters, as defined by `attribute` or `class`.
+    Class,
+    /// The name of a `swapcd`, `swapdd` or `swapcc` rule.
+    Swap,
+    /// An emphasis class, as defined by `emphclass`.
+    Emphasis,
+}
+
+/// The place a name is defined or referred to.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NameLocation<'f> {
+    pub file: &'f str,
+    /// The zero based index of the line in the file.
+    pub line: usize,
+    /// The byte span of the name in the file, without a leading `%`.
+    pub span: Range<usize>,
+}
+
+/// The name a rule defines, if any.
+pub fn defined_name<'a>(rule: &Rule<'a>) -> Option<(&'a str, NameKind)> {
+    match rule {
+        Rule::Attribute { name, .. } | Rule::Class { name, .. } => Some((name, NameKind::Class)),
+        Rule::Swapcd { name, .. } | Rule::Swapdd { name, .. } | Rule::Swapcc { name, .. } => Some((name, NameKind::Swap)),
+        Rule::Emphclass { name } => Some((name, NameKind::Emphasis)),
+        _ => None,
+    }
+}
+
+/// The definitions of `name` as a `kind` in `files`, in file and line
+/// order. More than one definition means the name is redefined.
+pub fn definition_of<'f>(files: &[(&'f str, &Table)], name: &str, kind: NameKind) -> Vec<NameLocation<'f>> {
+    let mut definitions = Vec::new();
+    for (file, table) in files {
+        for (line, parsed) in table.lines().iter().enumerate() {
+            let Line::Rule { rule, .. } = parsed else { continue };
+            if defined_name(rule) != Some((name, kind)) {
+                continue;
+            }
+            if let Some(operand) = table.operands(line).into_iter().find(|operand| operand.kind == OperandKind::Name) {
+                definitions.push(NameLocation { file, line, span: operand.span });
+            }
+        }
+    }
+    definitions
+}
+
+/// The spans of the `%name` references in an operand starting at
+/// `offset`.
+fn percent_references(text: &str, offset: usize, name: &str) -> Vec<Range<usize>> {
+    text.match_indices('%')
+        .filter_map(|(i, _)| {
+            let start = i + 1;
+            let len = text[start..].find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(text.len() - start);
+            (&text[start..start + len] == name).then(|| offset + start..offset + start + len)
+        })
+        .collect()
+}
+
+/// The references to `name` as a `kind` in `files`, in file and line
+/// order. Definitions are not references. As `%name` refers to a
+/// class or a swap name alike, these are references of both kinds.
+pub fn references_of<'f>(files: &[(&'f str, &Table)], name: &str, kind: NameKind) -> Vec<NameLocation<'f>> {
+    let mut references = Vec::new();
+    for (file, table) in files {
+        for (line, parsed) in table.lines().iter().enumerate() {
+            let Line::Rule { rule, .. } = parsed else { continue };
+            for operand in table.operands(line) {
+                let text = &table.source()[operand.span.clone()];
+                let spans = match (kind, rule, operand.kind) {
+                    (NameKind::Class, Rule::Base { .. }, OperandKind::Name) if text == name => vec![operand.span],
+                    (NameKind::Emphasis, Rule::Modeletter { .. } | Rule::Begmodeword { .. }, _)
+                        if operand.index == 0 && text == name => vec![operand.span],
+                    (NameKind::Class | NameKind::Swap, _, OperandKind::Test | OperandKind::Action | OperandKind::Pattern) =>
+                        percent_references(text, operand.span.start, name),
+                    _ => continue,
+                };
+                references.extend(spans.into_iter().map(|span| NameLocation { file, line, span }));
+            }
+        }
+    }
+    references
+}
+
+/// Why a rename was refused.
+#[derive(PartialEq, Debug)]
+pub enum RenameError<'f> {
+    /// The name is not defined as the kind in any of the files.
+    Undefined,
+    /// The name of a class or a swap is defined as both, so the
+    /// references of the two cannot be told apart.
+    Ambiguous,
+    /// The new name is not made of ASCII letters only.
+    InvalidName,
+    /// The new name is already defined, e.g. at this location.
+    Collision(NameLocation<'f>),
+}
+
+/// The edits of one file.
+#[derive(PartialEq, Debug)]
+pub struct FileEdits<'f> {
+    pub file: &'f str,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Renames `name` defined as a `kind` to `new_name` in all definitions
+/// and references across `files`. Returns the edits of every file that
+/// changes, in the order of `files`. The new name must not be defined
+/// yet, as a class or a swap name for these, as an emphasis class for
+/// an emphasis class.
+pub fn rename<'f>(files: &[(&'f str, &Table)], name: &str, kind: NameKind, new_name: &str)
+                  -> Result<Vec<FileEdits<'f>>, RenameError<'f>> {
+    let definitions = definition_of(files, name, kind);
+    if definitions.is_empty() {
+        return Err(RenameError::Undefined);
+    }
+    // `%name` does not tell classes and swap names apart, emphasis
+    // classes have names of their own
+    let kinds = match kind {
+        NameKind::Class | NameKind::Swap => vec![NameKind::Class, NameKind::Swap],
+        NameKind::Emphasis => vec![NameKind::Emphasis],
+    };
+    if kinds.iter().any(|other| *other != kind && !definition_of(files, name, *other).is_empty()) {
+        return Err(RenameError::Ambiguous);
+    }
+    if new_name.is_empty() || !new_name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(RenameError::InvalidName);
+    }
+    if new_name == name {
+        return Ok(Vec::new());
+    }
+    for other in kinds {
+        if let Some(existing) = definition_of(files, new_name, other).into_iter().next() {
+            return Err(RenameError::Collision(existing));
+        }
+    }
+    let locations: Vec<NameLocation<'f>> = definitions.into_iter().chain(references_of(files, name, kind)).collect();
+    Ok(files
+        .iter()
+        .filter_map(|(file, _)| {
+            let mut edits: Vec<TextEdit> = locations
+                .iter()
+                .filter(|location| location.file == *file)
+                .map(|location| TextEdit { range: location.span.clone(), new_text: new_name.to_string() })
+                .collect();
+            edits.sort_by_key(|edit| edit.range.start);
+            (!edits.is_empty()).then_some(FileEdits { file, edits })
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "chardef", feature = "translation", feature = "multipass"))]
+mod tests {
+    use super::*;
+    use crate::edit::apply;
+
+    const MAIN: &str = concat!("include vowels.tbl\n",
+                               "swapcc dropped ab cd\n",
+                               "context %vowels[%vowels] %dropped\n",
+                               "noback match %vowels a - 1\n",
+                               "base vowels A a\n");
+    const VOWELS: &str = "attribute vowels aeiou\nattribute vowelsx y\n";
+
+    #[test]
+    fn definition_of_test() {
+        let (_, main) = Table::parse(MAIN).unwrap();
+        let (_, vowels) = Table::parse(VOWELS).unwrap();
+        let files = [("main.tbl", &main), ("vowels.tbl", &vowels)];
+        assert_eq!(definition_of(&files, "vowels", NameKind::Class),
+                   vec![NameLocation { file: "vowels.tbl", line: 0, span: 10..16 }]);
+        assert_eq!(definition_of(&files, "dropped", NameKind::Swap),
+                   vec![NameLocation { file: "main.tbl", line: 1, span: 26..33 }]);
+        assert_eq!(definition_of(&files, "dropped", NameKind::Class), vec![]);
+        assert_eq!(&MAIN[26..33], "dropped");
+    }
+
+    #[test]
+    fn references_of_test() {
+        let (_, main) = Table::parse(MAIN).unwrap();
+        let (_, vowels) = Table::parse(VOWELS).unwrap();
+        let files = [("main.tbl", &main), ("vowels.tbl", &vowels)];
+        let references = references_of(&files, "vowels", NameKind::Class);
+        assert_eq!(references.iter().map(|location| location.line).collect::<Vec<_>>(), vec![2, 2, 3, 4]);
+        assert!(references.iter().all(|location| location.file == "main.tbl" && &MAIN[location.span.clone()] == "vowels"));
+        assert_eq!(references_of(&files, "dropped", NameKind::Swap).len(), 1);
+        assert_eq!(references_of(&files, "vowelsx", NameKind::Class), vec![]);
+    }
+
+    #[test]
+    #[cfg(feature = "emphasis")]
+    fn emphasis_test() {
+        let source = concat!("emphclass italic\n",
+                             "emphclass bold\n",
+                             "modeletter italic 46\n",
+                             "begmodeword italic 46-46\n",
+                             "attribute italic ab\n",
+                             "context %italic $a\n");
+        let (_, table) = Table::parse(source).unwrap();
+        let files = [("emphasis.tbl", &table)];
+        assert_eq!(defined_name(&Rule::Emphclass { name: "bold" }), Some(("bold", NameKind::Emphasis)));
+        assert_eq!(definition_of(&files, "italic", NameKind::Emphasis),
+                   vec![NameLocation { file: "emphasis.tbl", line: 0, span: 10..16 }]);
+        assert_eq!(references_of(&files, "italic", NameKind::Emphasis),
+                   vec![NameLocation { file: "emphasis.tbl", line: 2, span: 43..49 },
+                        NameLocation { file: "emphasis.tbl", line: 3, span: 65..71 }]);
+        assert_eq!(references_of(&files, "italic", NameKind::Class).iter().map(|location| location.line).collect::<Vec<_>>(),
+                   vec![5]);
+        assert_eq!(references_of(&files, "bold", NameKind::Emphasis), vec![]);
+    }
+
+    #[test]
+    fn rename_test() {
+        let (_, main) = Table::parse(MAIN).unwrap();
+        let (_, vowels) = Table::parse(VOWELS).unwrap();
+        let files = [("main.tbl", &main), ("vowels.tbl", &vowels)];
+        let edits = rename(&files, "vowels", NameKind::Class, "vocals").unwrap();
+        assert_eq!(edits.iter().map(|edits| edits.file).collect::<Vec<_>>(), vec!["main.tbl", "vowels.tbl"]);
+        assert_eq!(apply(MAIN, &edits[0].edits),
+                   concat!("include vowels.tbl\n",
+                           "swapcc dropped ab cd\n",
+                           "context %vocals[%vocals] %dropped\n",
+                           "noback match %vocals a - 1\n",
+                           "base vocals A a\n"));
+        assert_eq!(apply(VOWELS, &edits[1].edits), "attribute vocals aeiou\nattribute vowelsx y\n");
+        let edits = rename(&files, "dropped", NameKind::Swap, "gone").unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].edits.len(), 2);
+        assert_eq!(rename(&files, "vowels", NameKind::Class, "vowels"), Ok(vec![]));
+    }
+
+    #[test]
+    #[cfg(feature = "emphasis")]
+    fn rename_emphasis_test() {
+        let classes = "emphclass italic\nemphclass bold\nattribute script ab\n";
+        let indicators = "modeletter italic 46\nbegmodeword italic 46-46\ncontext %italic $a\n";
+        let (_, classes_table) = Table::parse(classes).unwrap();
+        let (_, indicators_table) = Table::parse(indicators).unwrap();
+        let files = [("classes.tbl", &classes_table), ("indicators.tbl", &indicators_table)];
+        let edits = rename(&files, "italic", NameKind::Emphasis, "slanted").unwrap();
+        assert_eq!(edits.iter().map(|edits| edits.file).collect::<Vec<_>>(), vec!["classes.tbl", "indicators.tbl"]);
+        assert_eq!(apply(classes, &edits[0].edits), "emphclass slanted\nemphclass bold\nattribute script ab\n");
+        assert_eq!(apply(indicators, &edits[1].edits),
+                   "modeletter slanted 46\nbegmodeword slanted 46-46\ncontext %italic $a\n");
+        assert_eq!(rename(&files, "italic", NameKind::Emphasis, "bold"),
+                   Err(RenameError::Collision(NameLocation { file: "classes.tbl", line: 1, span: 27..31 })));
+        // classes have names of their own
+        assert!(rename(&files, "italic", NameKind::Emphasis, "script").is_ok());
+        assert_eq!(rename(&files, "italic", NameKind::Class, "slanted"), Err(RenameError::Undefined));
+    }
+
+    #[test]
+    fn rename_refused_test() {
+        let (_, main) = Table::parse(MAIN).unwrap();
+        let (_, vowels) = Table::parse(VOWELS).unwrap();
+        let files = [("main.tbl", &main), ("vowels.tbl", &vowels)];
+        assert_eq!(rename(&files, "vowels", NameKind::Class, "vowelsx"),
+                   Err(RenameError::Collision(NameLocation { file: "vowels.tbl", line: 1, span: 33..40 })));
+        assert_eq!(rename(&files, "vowels", NameKind::Class, "dropped"),
+                   Err(RenameError::Collision(NameLocation { file: "main.tbl", line: 1, span: 26..33 })));
+        assert_eq!(rename(&files, "vowels", NameKind::Class, "vo wels"), Err(RenameError::InvalidName));
+        assert_eq!(rename(&files, "vowels", NameKind::Swap, "vocals"), Err(RenameError::Undefined));
+        let (_, both) = Table::parse("swapcc vowels a b\n").unwrap();
+        let files = [("main.tbl", &main), ("vowels.tbl", &vowels), ("both.tbl", &both)];
+        assert_eq!(rename(&files, "vowels", NameKind::Class, "vocals"), Err(RenameError::Ambiguous));
+    }
+}
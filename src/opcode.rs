@@ -0,0 +1,376 @@
+//! Metadata about the opcodes known to the parser, e.g. for inline
+//! documentation in editors.
+//!
+//! All opcodes are listed in a single registry below. Each entry
+//! names the opcode, its keyword, its category, whether it accepts
+//! prefixes, its operands and a short description. Adding an opcode
+//! to the parser means adding an entry to the registry.
+
+use std::fmt;
+
+use crate::parser::Rule;
+
+/// The category of an opcode, following the sections of the liblouis
+/// manual.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Category {
+    /// Opcodes that define characters.
+    CharDef,
+    /// Opcodes that define how characters and words are translated.
+    Translation,
+    /// Opcodes for emphasis, capitalization and other indicators.
+    Emphasis,
+    /// Opcodes for the multipass translation stages.
+    Multipass,
+    /// Opcodes that are about the table itself.
+    Meta,
+}
+
+/// The kind of an operand of an opcode.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum OperandKind {
+    Filename,
+    /// A single character.
+    Char,
+    /// A sequence of characters.
+    Chars,
+    Dots,
+    Number,
+    /// Either `before` or `after`.
+    Position,
+    /// The name of e.g. a class or a grouping.
+    Name,
+    /// The test of a multipass rule, which matches the input.
+    Test,
+    /// The action of a multipass rule, which replaces what the test
+    /// matched.
+    Action,
+    /// The context before or after the characters of a `match` rule.
+    Pattern,
+}
+
+impl fmt::Display for OperandKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OperandKind::Filename => write!(f, "filename"),
+            OperandKind::Char => write!(f, "character"),
+            OperandKind::Chars => write!(f, "characters"),
+            OperandKind::Dots => write!(f, "dots"),
+            OperandKind::Number => write!(f, "number"),
+            OperandKind::Position => write!(f, "before|after"),
+            OperandKind::Name => write!(f, "name"),
+            OperandKind::Test => write!(f, "test"),
+            OperandKind::Action => write!(f, "action"),
+            OperandKind::Pattern => write!(f, "pattern"),
+        }
+    }
+}
+
+macro_rules! opcodes {
+    ($($variant:ident => $keyword:literal, $category:ident, $prefixes:literal, [$($operand:ident),*], $description:literal;)*) => {
+        /// All opcodes known to the parser. The variants are named like
+        /// the corresponding `Rule` variants.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum Opcode {
+            $($variant,)*
+        }
+
+        impl Opcode {
+            pub const ALL: &'static [Opcode] = &[$(Opcode::$variant,)*];
+
+            const KEYWORDS: &'static [&'static str] = &[$($keyword,)*];
+
+            pub fn keyword(self) -> &'static str {
+                match self {
+                    $(Opcode::$variant => $keyword,)*
+                }
+            }
+
+            pub fn from_keyword(keyword: &str) -> Option<Opcode> {
+                match keyword {
+                    $($keyword => Some(Opcode::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub fn category(self) -> Category {
+                match self {
+                    $(Opcode::$variant => Category::$category,)*
+                }
+            }
+
+            /// Whether the opcode can be preceded by `noback`, `nofor`
+            /// or `nocross`.
+            pub fn accepts_prefixes(self) -> bool {
+                match self {
+                    $(Opcode::$variant => $prefixes,)*
+                }
+            }
+
+            pub fn operands(self) -> &'static [OperandKind] {
+                match self {
+                    $(Opcode::$variant => &[$(OperandKind::$operand),*],)*
+                }
+            }
+
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(Opcode::$variant => $description,)*
+                }
+            }
+        }
+
+        impl Rule<'_> {
+            pub fn opcode(&self) -> Opcode {
+                match self {
+                    $(Rule::$variant { .. } => Opcode::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+/// `==` on strings, which is not available in const contexts.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+impl Opcode {
+    /// Whether `word` is the keyword of an opcode. Unlike
+    /// `from_keyword` this can be evaluated at compile time, as the
+    /// `rule!` macro does.
+    pub const fn is_keyword(word: &str) -> bool {
+        let mut i = 0;
+        while i < Opcode::KEYWORDS.len() {
+            if str_eq(Opcode::KEYWORDS[i], word) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+opcodes! {
+    Include => "include", Meta, false, [Filename],
+        "Includes the rules of another table file.";
+    Undefined => "undefined", CharDef, false, [Dots],
+        "Defines the dots used for characters that have no definition.";
+    Display => "display", CharDef, true, [Chars, Dots],
+        "Defines how a character is shown on a braille display, independent of translation.";
+    Space => "space", CharDef, true, [Char, Dots],
+        "Defines a character as whitespace.";
+    Multind => "multind", Translation, true, [Chars, Dots],
+        "Defines the dots for a combination of several indicators.";
+    Punctuation => "punctuation", CharDef, true, [Char, Dots],
+        "Defines a character as punctuation.";
+    Digit => "digit", CharDef, false, [Char, Dots],
+        "Defines a character as a digit.";
+    Litdigit => "litdigit", CharDef, false, [Chars, Dots],
+        "Defines the dots of digits in literary context.";
+    Letter => "letter", CharDef, true, [Char, Dots],
+        "Defines a character as a letter.";
+    Uppercase => "uppercase", CharDef, true, [Char, Dots],
+        "Defines a character as an uppercase letter.";
+    Lowercase => "lowercase", CharDef, true, [Char, Dots],
+        "Defines a character as a lowercase letter.";
+    Sign => "sign", CharDef, true, [Char, Dots],
+        "Defines a character as a sign, e.g. a symbol such as % or $.";
+    Math => "math", CharDef, true, [Char, Dots],
+        "Defines a character as a mathematical symbol.";
+    Uplow => "uplow", CharDef, false, [Chars, Dots],
+        "Defines an uppercase and a lowercase letter. Deprecated, use uppercase and lowercase instead.";
+    Base => "base", CharDef, false, [Name, Char, Char],
+        "Defines a character as derived from a base character with an attribute, e.g. uppercase.";
+    Attribute => "attribute", CharDef, false, [Name, Chars],
+        "Adds characters to a user defined character class.";
+    Class => "class", CharDef, false, [Name, Chars],
+        "Adds characters to a character class. Deprecated, use attribute instead.";
+    Emphclass => "emphclass", Emphasis, false, [Name],
+        "Defines an emphasis class, e.g. italic, for the emphasis opcodes.";
+    Modeletter => "modeletter", Emphasis, true, [Chars, Dots],
+        "Defines the indicator for a single letter in the given mode.";
+    Capsletter => "capsletter", Emphasis, true, [Dots],
+        "Defines the indicator for a single capital letter.";
+    Begmodeword => "begmodeword", Emphasis, true, [Chars, Dots],
+        "Defines the indicator for the beginning of a word in the given mode.";
+    Begcapsword => "begcapsword", Emphasis, true, [Dots],
+        "Defines the indicator for the beginning of a capitalized word.";
+    Endcapsword => "endcapsword", Emphasis, true, [Dots],
+        "Defines the indicator for the end of a capitalized word.";
+    Capsmodechars => "capsmodechars", Emphasis, false, [Chars],
+        "Defines characters that do not end capitalization mode.";
+    Begcaps => "begcaps", Emphasis, false, [Dots],
+        "Defines the indicator for the beginning of a capitalized block.";
+    Endcaps => "endcaps", Emphasis, false, [Dots],
+        "Defines the indicator for the end of a capitalized block.";
+    Begcapsphrase => "begcapsphrase", Emphasis, false, [Dots],
+        "Defines the indicator for the beginning of a capitalized phrase.";
+    Endcapsphrase => "endcapsphrase", Emphasis, false, [Position, Dots],
+        "Defines the indicator for the end of a capitalized phrase, placed before or after the last word.";
+    Lencapsphrase => "lencapsphrase", Emphasis, false, [Number],
+        "Defines the number of capitalized words that make up a capitalized phrase.";
+    Capsnocont => "capsnocont", Emphasis, false, [],
+        "Prevents contractions in capitalized words.";
+    Letsign => "letsign", Emphasis, false, [Dots],
+        "Defines the letter sign, which marks a letter that could be mistaken for a contraction or a digit.";
+    Noletsign => "noletsign", Emphasis, false, [Chars],
+        "Lists letters that never take a letter sign when they stand alone.";
+    Noletsignbefore => "noletsignbefore", Emphasis, false, [Chars],
+        "Lists characters that make a letter sign unnecessary when they precede a single letter.";
+    Noletsignafter => "noletsignafter", Emphasis, false, [Chars],
+        "Lists characters that make a letter sign unnecessary when they follow a single letter.";
+    Nocontractsign => "nocontractsign", Emphasis, false, [Dots],
+        "Defines the sign that marks letters which are not a contraction.";
+    Numsign => "numsign", Emphasis, false, [Dots],
+        "Defines the number sign, which starts a number.";
+    Nonumsign => "nonumsign", Emphasis, false, [Dots],
+        "Defines the sign that ends numeric mode, e.g. before a letter that follows a number.";
+    Numericmodechars => "numericmodechars", Emphasis, false, [Chars],
+        "Lists characters that keep numeric mode active.";
+    Largesign => "largesign", Translation, false, [Chars, Dots],
+        "Translates a word that is not separated by a space from adjacent large signs.";
+    Syllable => "syllable", Translation, false, [Chars, Dots],
+        "Translates a syllable, preventing other contractions inside it.";
+    Joinword => "joinword", Translation, false, [Chars, Dots],
+        "Translates a word and joins it to the following word.";
+    Grouping => "grouping", Translation, false, [Name, Chars, Dots],
+        "Defines a pair of characters, such as parentheses, that enclose a group.";
+    Match => "match", Translation, true, [Pattern, Chars, Pattern, Dots],
+        "Translates characters that are preceded and followed by a context matching the patterns.";
+    Backmatch => "backmatch", Translation, true, [Pattern, Chars, Pattern, Dots],
+        "Back-translates dots that are preceded and followed by a context matching the patterns.";
+    Literal => "literal", Translation, false, [Chars],
+        "Marks a sequence of characters that is not translated but passed through as computer braille.";
+    Always => "always", Translation, true, [Chars, Dots],
+        "Translates characters wherever they occur.";
+    Word => "word", Translation, true, [Chars, Dots],
+        "Translates characters only if they form a whole word.";
+    Nocont => "nocont", Translation, false, [Chars],
+        "Marks a sequence of characters that is never contracted.";
+    Compbrl => "compbrl", Translation, false, [Chars],
+        "Marks a sequence of characters that is passed through as computer braille within a word.";
+    Comp6 => "comp6", Translation, true, [Char, Dots],
+        "Defines the 6-dot computer braille of a character.";
+    Replace => "replace", Translation, false, [Chars, Chars],
+        "Replaces characters with other characters, or removes them, before translation.";
+    Repeated => "repeated", Translation, true, [Chars, Dots],
+        "Translates a sequence of repeated characters only once, e.g. a line of dashes.";
+    Repword => "repword", Translation, true, [Chars, Dots],
+        "Translates a word that is repeated with a hyphen in between, e.g. in Malay.";
+    Rependword => "rependword", Translation, true, [Chars, Dots],
+        "Like repword, but with other dots if the repetition is only the end of the word.";
+    Begword => "begword", Translation, true, [Chars, Dots],
+        "Translates characters only at the beginning of a word.";
+    Midword => "midword", Translation, true, [Chars, Dots],
+        "Translates characters only in the middle of a word.";
+    Endword => "endword", Translation, true, [Chars, Dots],
+        "Translates characters only at the end of a word.";
+    Partword => "partword", Translation, true, [Chars, Dots],
+        "Translates characters only if they are part of a longer word.";
+    Begmidword => "begmidword", Translation, true, [Chars, Dots],
+        "Translates characters at the beginning or in the middle of a word.";
+    Midendword => "midendword", Translation, true, [Chars, Dots],
+        "Translates characters in the middle or at the end of a word.";
+    Prfxword => "prfxword", Translation, true, [Chars, Dots],
+        "Translates characters at the beginning of a word or if they are a whole word.";
+    Sufword => "sufword", Translation, true, [Chars, Dots],
+        "Translates characters at the end of a word or if they are a whole word.";
+    Lowword => "lowword", Translation, true, [Chars, Dots],
+        "Translates characters only if they form a whole word surrounded by whitespace.";
+    Prepunc => "prepunc", Translation, true, [Chars, Dots],
+        "Translates characters only if they are punctuation before a word.";
+    Postpunc => "postpunc", Translation, true, [Chars, Dots],
+        "Translates characters only if they are punctuation after a word.";
+    Begnum => "begnum", Translation, true, [Chars, Dots],
+        "Translates characters only at the beginning of a number.";
+    Midnum => "midnum", Translation, true, [Chars, Dots],
+        "Translates characters only in the middle of a number.";
+    Endnum => "endnum", Translation, true, [Chars, Dots],
+        "Translates characters only at the end of a number.";
+    Joinnum => "joinnum", Translation, true, [Chars, Dots],
+        "Translates characters and joins them to a following number.";
+    Contraction => "contraction", Translation, false, [Chars],
+        "Marks characters that look like a contraction, so that a letter sign is put before them.";
+    Exactdots => "exactdots", Translation, false, [Dots],
+        "Translates an @ followed by the dot numbers in the input to exactly these dots.";
+    Swapcd => "swapcd", Multipass, false, [Name, Chars, Dots],
+        "Defines a class of characters that multipass rules swap for dots.";
+    Swapdd => "swapdd", Multipass, false, [Name, Dots, Dots],
+        "Defines a class of dot patterns that multipass rules swap for other dot patterns.";
+    Swapcc => "swapcc", Multipass, false, [Name, Chars, Chars],
+        "Defines a class of characters that multipass rules swap for other characters.";
+    Context => "context", Multipass, true, [Test, Action],
+        "Applies an action where the input matches a test, during the main translation pass.";
+    Correct => "correct", Multipass, true, [Test, Action],
+        "Applies an action where the input matches a test, before translation.";
+    Pass2 => "pass2", Multipass, true, [Test, Action],
+        "Applies an action where the braille matches a test, in the second pass.";
+    Pass3 => "pass3", Multipass, true, [Test, Action],
+        "Applies an action where the braille matches a test, in the third pass.";
+    Pass4 => "pass4", Multipass, true, [Test, Action],
+        "Applies an action where the braille matches a test, in the fourth pass.";
+}
+
+impl Opcode {
+    /// Whether the opcode is only supported for compatibility and has
+    /// been superseded by other opcodes.
+    pub fn is_deprecated(self) -> bool {
+        matches!(self, Opcode::Uplow | Opcode::Class)
+    }
+}
+
+impl fmt::Display for Opcode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.keyword())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "emphasis")]
+    use crate::parser::endcapsphrase;
+
+    #[test]
+    fn keyword_test() {
+        assert_eq!(Opcode::Joinword.keyword(), "joinword");
+        assert_eq!(Opcode::from_keyword("joinword"), Some(Opcode::Joinword));
+        assert_eq!(Opcode::from_keyword("joinwords"), None);
+        for opcode in Opcode::ALL {
+            assert_eq!(Opcode::from_keyword(opcode.keyword()), Some(*opcode));
+            assert!(Opcode::is_keyword(opcode.keyword()));
+        }
+        const { assert!(!Opcode::is_keyword("joinwords")) };
+        assert!(!Opcode::is_keyword("joinwor"));
+    }
+
+    #[test]
+    fn metadata_test() {
+        assert_eq!(Opcode::Endcapsphrase.operands(), &[OperandKind::Position, OperandKind::Dots]);
+        assert_eq!(Opcode::Endcapsphrase.category(), Category::Emphasis);
+        assert!(!Opcode::Endcapsphrase.description().is_empty());
+        assert!(Opcode::Display.accepts_prefixes());
+        assert!(!Opcode::Include.accepts_prefixes());
+        assert!(Opcode::Class.is_deprecated());
+        assert!(!Opcode::Attribute.is_deprecated());
+    }
+
+    #[test]
+    #[cfg(feature = "emphasis")]
+    fn rule_opcode_test() {
+        let (_, rule) = endcapsphrase("endcapsphrase after 45").unwrap();
+        assert_eq!(rule.opcode(), Opcode::Endcapsphrase);
+    }
+}
@@ -0,0 +1,541 @@
+//! The parser for liblouis tables.
+//!
+//! This module defines the AST and the parsers for lines and tables.
+//! The parsers for the parts of a line live in submodules and are all
+//! re-exported from here:
+//!
+//! - `operands`: operands shared by many opcodes, most notably dots
+//! - `prefixes`: the `noback`, `nofor` and `nocross` prefixes
+//! - `opcodes`: one module per opcode family, see `opcode::Category`
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::line_ending;
+use nom::character::complete::not_line_ending;
+use nom::character::complete::space0;
+use nom::character::complete::space1;
+use nom::combinator::all_consuming;
+use nom::combinator::map;
+use nom::sequence::terminated;
+use nom::sequence::tuple;
+use nom::error::Error;
+use nom::Err;
+
+use nom::IResult;
+use std::fmt;
+
+pub mod operands;
+pub mod prefixes;
+pub mod opcodes;
+
+pub use operands::*;
+pub use prefixes::*;
+#[cfg(feature = "meta")]
+pub use opcodes::meta::*;
+#[cfg(feature = "chardef")]
+pub use opcodes::chardef::*;
+#[cfg(feature = "translation")]
+pub use opcodes::translation::*;
+#[cfg(feature = "emphasis")]
+pub use opcodes::emphasis::*;
+#[cfg(feature = "multipass")]
+pub use opcodes::multipass::*;
+pub use opcodes::rule;
+
+#[derive(PartialEq, Debug)]
+pub enum Line<'a> {
+    Empty,
+    Comment { comment: &'a str },
+    Rule { rule: Rule<'a>, comment: &'a str },
+    /// A line that does not parse, kept verbatim without line ending,
+    /// see `Table::parse`.
+    Invalid { text: &'a str },
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Rule<'a> {
+    Include { filename: &'a str },
+    Undefined { dots: BrailleChars },
+    Display { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Space { ch: char, dots: BrailleChars, prefixes: Prefixes},
+    Multind { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Punctuation { ch: char, dots: BrailleChars, prefixes: Prefixes},
+    Digit { ch: char, dots: BrailleChars },
+    Litdigit { chars: &'a str, dots: BrailleChars },
+    Letter { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    Uppercase { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    Lowercase { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    Sign { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    Math { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    /// The deprecated predecessor of `uppercase` and `lowercase`.
+    Uplow { upper: char, lower: char, dots: BrailleChars, lower_dots: Option<BrailleChars> },
+    Base { attribute: &'a str, derived: char, base: char },
+    Attribute { name: &'a str, chars: &'a str },
+    /// The deprecated predecessor of `attribute`.
+    Class { name: &'a str, chars: &'a str },
+    Emphclass { name: &'a str },
+    Modeletter { chars: &'a str, dots: BrailleChars, prefixes: Prefixes},
+    Capsletter { dots: BrailleChars, prefixes: Prefixes},
+    Begmodeword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes},
+    Begcapsword { dots: BrailleChars, prefixes: Prefixes},
+    Endcapsword { dots: BrailleChars, prefixes: Prefixes},
+    Capsmodechars { chars: &'a str},
+    Begcaps { dots: BrailleChars},
+    Endcaps { dots: BrailleChars},
+    Begcapsphrase { dots: BrailleChars},
+    Endcapsphrase { dots: BrailleChars, position: Position},
+    Lencapsphrase { length: u8},
+    Capsnocont,
+    Letsign { dots: BrailleChars },
+    Noletsign { chars: &'a str },
+    Noletsignbefore { chars: &'a str },
+    Noletsignafter { chars: &'a str },
+    Nocontractsign { dots: BrailleChars },
+    Numsign { dots: BrailleChars },
+    Nonumsign { dots: BrailleChars },
+    Numericmodechars { chars: &'a str },
+    Largesign { word: &'a str, dots: BrailleChars },
+    Syllable { word: &'a str, dots: BrailleChars },
+    Joinword { word: &'a str, dots: BrailleChars },
+    Grouping { name: &'a str, open: char, close: char, open_dots: BrailleChars, close_dots: BrailleChars },
+    Match { pre: &'a str, chars: &'a str, post: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Backmatch { pre: &'a str, chars: &'a str, post: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Literal { chars: &'a str },
+    Always { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Word { chars: &'a str, dots: Braille, prefixes: Prefixes },
+    Nocont { chars: &'a str },
+    Compbrl { chars: &'a str },
+    Comp6 { ch: char, dots: BrailleChars, prefixes: Prefixes },
+    Replace { from: &'a str, to: Option<&'a str> },
+    Repeated { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Repword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Rependword { chars: &'a str, dots: BrailleChars, otherdots: BrailleChars, prefixes: Prefixes },
+    Begword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Midword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Endword { chars: &'a str, dots: Braille, prefixes: Prefixes },
+    Partword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Begmidword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Midendword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Prfxword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Sufword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Lowword { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Prepunc { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Postpunc { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Begnum { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Midnum { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Endnum { chars: &'a str, dots: Braille, prefixes: Prefixes },
+    Joinnum { chars: &'a str, dots: BrailleChars, prefixes: Prefixes },
+    Contraction { chars: &'a str },
+    Exactdots { dots: BrailleChars },
+    Swapcd { name: &'a str, chars: &'a str, dots: Vec<BrailleChars> },
+    Swapdd { name: &'a str, from: Vec<BrailleChars>, to: Vec<BrailleChars> },
+    Swapcc { name: &'a str, from: &'a str, to: &'a str },
+    Context { test: &'a str, action: &'a str, prefixes: Prefixes },
+    Correct { test: &'a str, action: &'a str, prefixes: Prefixes },
+    Pass2 { test: &'a str, action: &'a str, prefixes: Prefixes },
+    Pass3 { test: &'a str, action: &'a str, prefixes: Prefixes },
+    Pass4 { test: &'a str, action: &'a str, prefixes: Prefixes },
+}
+
+impl Rule<'_> {
+    /// The characters and dots defined by a character-definition rule.
+    /// An `uplow` rule defines two characters, like the rules of
+    /// `lower_uplow`.
+    pub fn char_definitions(&self) -> Vec<(char, &BrailleChars)> {
+        match self {
+            Rule::Space { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Punctuation { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Digit { ch, dots } => vec![(*ch, dots)],
+            Rule::Letter { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Uppercase { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Lowercase { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Sign { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Math { ch, dots, .. } => vec![(*ch, dots)],
+            Rule::Uplow { upper, lower, dots, lower_dots } => vec![(*upper, dots), (*lower, lower_dots.as_ref().unwrap_or(dots))],
+            _ => vec![],
+        }
+    }
+
+    /// The `uppercase` and `lowercase` rules that replace a deprecated
+    /// `uplow` rule.
+    pub fn lower_uplow(&self) -> Option<[Rule<'static>; 2]> {
+        match self {
+            Rule::Uplow { upper, lower, dots, lower_dots } => Some([
+                Rule::Uppercase { ch: *upper, dots: dots.clone(), prefixes: Prefixes::empty() },
+                Rule::Lowercase { ch: *lower, dots: lower_dots.as_ref().unwrap_or(dots).clone(), prefixes: Prefixes::empty() },
+            ]),
+            _ => None,
+        }
+    }
+}
+
+/// Prints a rule in canonical table syntax, i.e. with single spaces
+/// between operands and dots in ascending order.
+impl fmt::Display for Rule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Include { filename } => write!(f, "include {}", filename),
+            Rule::Undefined { dots } => write!(f, "undefined {}", dots_to_string(dots)),
+            Rule::Display { chars, dots, prefixes } =>
+                write!(f, "{}display {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Space { ch, dots, prefixes } =>
+                write!(f, "{}space {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Multind { chars, dots, prefixes } =>
+                write!(f, "{}multind {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Punctuation { ch, dots, prefixes } =>
+                write!(f, "{}punctuation {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Digit { ch, dots } => write!(f, "digit {} {}", char_to_string(*ch), dots_to_string(dots)),
+            Rule::Litdigit { chars, dots } => write!(f, "litdigit {} {}", chars, dots_to_string(dots)),
+            Rule::Letter { ch, dots, prefixes } =>
+                write!(f, "{}letter {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Uppercase { ch, dots, prefixes } =>
+                write!(f, "{}uppercase {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Lowercase { ch, dots, prefixes } =>
+                write!(f, "{}lowercase {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Sign { ch, dots, prefixes } =>
+                write!(f, "{}sign {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Math { ch, dots, prefixes } =>
+                write!(f, "{}math {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Uplow { upper, lower, dots, lower_dots: None } =>
+                write!(f, "uplow {}{} {}", char_to_string(*upper), char_to_string(*lower), dots_to_string(dots)),
+            Rule::Base { attribute, derived, base } =>
+                write!(f, "base {} {} {}", attribute, char_to_string(*derived), char_to_string(*base)),
+            Rule::Attribute { name, chars } => write!(f, "attribute {} {}", name, chars),
+            Rule::Class { name, chars } => write!(f, "class {} {}", name, chars),
+            Rule::Uplow { upper, lower, dots, lower_dots: Some(lower_dots) } =>
+                write!(f, "uplow {}{} {},{}", char_to_string(*upper), char_to_string(*lower), dots_to_string(dots), dots_to_string(lower_dots)),
+            Rule::Emphclass { name } => write!(f, "emphclass {}", name),
+            Rule::Modeletter { chars, dots, prefixes } =>
+                write!(f, "{}modeletter {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Capsletter { dots, prefixes } =>
+                write!(f, "{}capsletter {}", prefixes_to_string(prefixes), dots_to_string(dots)),
+            Rule::Begmodeword { chars, dots, prefixes } =>
+                write!(f, "{}begmodeword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Begcapsword { dots, prefixes } =>
+                write!(f, "{}begcapsword {}", prefixes_to_string(prefixes), dots_to_string(dots)),
+            Rule::Endcapsword { dots, prefixes } =>
+                write!(f, "{}endcapsword {}", prefixes_to_string(prefixes), dots_to_string(dots)),
+            Rule::Capsmodechars { chars } => write!(f, "capsmodechars {}", chars),
+            Rule::Begcaps { dots } => write!(f, "begcaps {}", dots_to_string(dots)),
+            Rule::Endcaps { dots } => write!(f, "endcaps {}", dots_to_string(dots)),
+            Rule::Begcapsphrase { dots } => write!(f, "begcapsphrase {}", dots_to_string(dots)),
+            Rule::Endcapsphrase { dots, position } =>
+                write!(f, "endcapsphrase {} {}", position, dots_to_string(dots)),
+            Rule::Lencapsphrase { length } => write!(f, "lencapsphrase {}", length),
+            Rule::Capsnocont => write!(f, "capsnocont"),
+            Rule::Letsign { dots } => write!(f, "letsign {}", dots_to_string(dots)),
+            Rule::Noletsign { chars } => write!(f, "noletsign {}", chars),
+            Rule::Noletsignbefore { chars } => write!(f, "noletsignbefore {}", chars),
+            Rule::Noletsignafter { chars } => write!(f, "noletsignafter {}", chars),
+            Rule::Nocontractsign { dots } => write!(f, "nocontractsign {}", dots_to_string(dots)),
+            Rule::Numsign { dots } => write!(f, "numsign {}", dots_to_string(dots)),
+            Rule::Nonumsign { dots } => write!(f, "nonumsign {}", dots_to_string(dots)),
+            Rule::Numericmodechars { chars } => write!(f, "numericmodechars {}", chars),
+            Rule::Largesign { word, dots } => write!(f, "largesign {} {}", word, dots_to_string(dots)),
+            Rule::Syllable { word, dots } => write!(f, "syllable {} {}", word, dots_to_string(dots)),
+            Rule::Joinword { word, dots } => write!(f, "joinword {} {}", word, dots_to_string(dots)),
+            Rule::Grouping { name, open, close, open_dots, close_dots } =>
+                write!(f, "grouping {} {}{} {},{}", name, char_to_string(*open), char_to_string(*close),
+                       dots_to_string(open_dots), dots_to_string(close_dots)),
+            Rule::Match { pre, chars, post, dots, prefixes } =>
+                write!(f, "{}match {} {} {} {}", prefixes_to_string(prefixes), pre, chars, post, dots_to_string(dots)),
+            Rule::Backmatch { pre, chars, post, dots, prefixes } =>
+                write!(f, "{}backmatch {} {} {} {}", prefixes_to_string(prefixes), pre, chars, post, dots_to_string(dots)),
+            Rule::Literal { chars } => write!(f, "literal {}", chars),
+            Rule::Always { chars, dots, prefixes } =>
+                write!(f, "{}always {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Word { chars, dots, prefixes } =>
+                write!(f, "{}word {} {}", prefixes_to_string(prefixes), chars, braille_to_string(dots)),
+            Rule::Nocont { chars } => write!(f, "nocont {}", chars),
+            Rule::Compbrl { chars } => write!(f, "compbrl {}", chars),
+            Rule::Comp6 { ch, dots, prefixes } =>
+                write!(f, "{}comp6 {} {}", prefixes_to_string(prefixes), char_to_string(*ch), dots_to_string(dots)),
+            Rule::Replace { from, to: None } => write!(f, "replace {}", from),
+            Rule::Repeated { chars, dots, prefixes } =>
+                write!(f, "{}repeated {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Repword { chars, dots, prefixes } =>
+                write!(f, "{}repword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Rependword { chars, dots, otherdots, prefixes } =>
+                write!(f, "{}rependword {} {},{}", prefixes_to_string(prefixes), chars, dots_to_string(dots), dots_to_string(otherdots)),
+            Rule::Begword { chars, dots, prefixes } =>
+                write!(f, "{}begword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Midword { chars, dots, prefixes } =>
+                write!(f, "{}midword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Endword { chars, dots, prefixes } =>
+                write!(f, "{}endword {} {}", prefixes_to_string(prefixes), chars, braille_to_string(dots)),
+            Rule::Partword { chars, dots, prefixes } =>
+                write!(f, "{}partword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Begmidword { chars, dots, prefixes } =>
+                write!(f, "{}begmidword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Midendword { chars, dots, prefixes } =>
+                write!(f, "{}midendword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Prfxword { chars, dots, prefixes } =>
+                write!(f, "{}prfxword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Sufword { chars, dots, prefixes } =>
+                write!(f, "{}sufword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Lowword { chars, dots, prefixes } =>
+                write!(f, "{}lowword {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Prepunc { chars, dots, prefixes } =>
+                write!(f, "{}prepunc {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Postpunc { chars, dots, prefixes } =>
+                write!(f, "{}postpunc {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Begnum { chars, dots, prefixes } =>
+                write!(f, "{}begnum {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Midnum { chars, dots, prefixes } =>
+                write!(f, "{}midnum {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Endnum { chars, dots, prefixes } =>
+                write!(f, "{}endnum {} {}", prefixes_to_string(prefixes), chars, braille_to_string(dots)),
+            Rule::Joinnum { chars, dots, prefixes } =>
+                write!(f, "{}joinnum {} {}", prefixes_to_string(prefixes), chars, dots_to_string(dots)),
+            Rule::Contraction { chars } => write!(f, "contraction {}", chars),
+            Rule::Exactdots { dots } => write!(f, "exactdots @{}", dots_to_string(dots)),
+            Rule::Replace { from, to: Some(to) } => write!(f, "replace {} {}", from, to),
+            Rule::Swapcd { name, chars, dots } => write!(f, "swapcd {} {} {}", name, chars, dots_list_to_string(dots)),
+            Rule::Swapdd { name, from, to } =>
+                write!(f, "swapdd {} {} {}", name, dots_list_to_string(from), dots_list_to_string(to)),
+            Rule::Swapcc { name, from, to } => write!(f, "swapcc {} {} {}", name, from, to),
+            Rule::Context { test, action, prefixes } =>
+                write!(f, "{}context {} {}", prefixes_to_string(prefixes), test, action),
+            Rule::Correct { test, action, prefixes } =>
+                write!(f, "{}correct {} {}", prefixes_to_string(prefixes), test, action),
+            Rule::Pass2 { test, action, prefixes } =>
+                write!(f, "{}pass2 {} {}", prefixes_to_string(prefixes), test, action),
+            Rule::Pass3 { test, action, prefixes } =>
+                write!(f, "{}pass3 {} {}", prefixes_to_string(prefixes), test, action),
+            Rule::Pass4 { test, action, prefixes } =>
+                write!(f, "{}pass4 {} {}", prefixes_to_string(prefixes), test, action),
+        }
+    }
+}
+
+/// Prints a line in canonical table syntax, without line ending.
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Line::Empty => Ok(()),
+            Line::Comment { comment } => write!(f, "#{}", comment),
+            Line::Rule { rule, comment: "" } => write!(f, "{}", rule),
+            Line::Rule { rule, comment } => write!(f, "{} {}", rule, comment),
+            Line::Invalid { text } => write!(f, "{}", text),
+        }
+    }
+}
+
+pub fn end_comment(i: &str) -> IResult<&str, &str> {
+    let (input, (_, comment)) = tuple((space1, not_line_ending))(i)?;
+    Ok((input, comment))
+}
+
+/// Parses a single rule without line ending, e.g. for the `rule!`
+/// macro. Trailing whitespace is ignored.
+pub fn complete_rule(i: &str) -> Result<Rule<'_>, Err<Error<&str>>> {
+    let (_, rule) = all_consuming(terminated(rule, space0))(i)?;
+    Ok(rule)
+}
+
+pub fn rule_line(i: &str) -> IResult<&str, Line<'_>> {
+    let (input, (rule, comment, _)) = tuple((
+        rule,
+        alt((end_comment, space0)),
+        line_ending,
+    ))(i)?;
+    Ok((input, Line::Rule { rule, comment}))
+}
+
+pub fn comment_line(i: &str) -> IResult<&str, Line<'_>> {
+    let (input, (_, comment, _)) = tuple((tag("#"), not_line_ending, line_ending))(i)?;
+    Ok((input, Line::Comment { comment }))
+}
+
+pub fn empty_line(i: &str) -> IResult<&str, Line<'_>> {
+    let (input, (_, _)) = tuple((space0, line_ending))(i)?;
+    Ok((input, Line::Empty))
+}
+
+pub fn line(i: &str) -> IResult<&str, Line<'_>> {
+    let (input, rule) = alt((
+	rule_line,
+	comment_line,
+	empty_line,
+    ))(i)?;
+    Ok((input, rule))
+}
+
+/// Parses a last line that has no line ending, which `line` cannot,
+/// as it requires one.
+pub fn last_line(i: &str) -> IResult<&str, Line<'_>> {
+    all_consuming(alt((
+        map(tuple((rule, alt((end_comment, space0)))), |(rule, comment)| Line::Rule { rule, comment }),
+        map(tuple((tag("#"), not_line_ending)), |(_, comment)| Line::Comment { comment }),
+        map(space0, |_| Line::Empty),
+    )))(i)
+}
+
+/// Parses as many lines as possible. Parsing stops at the first line
+/// that does not parse, also if its opcode was recognized, so the
+/// unparsed rest of the input starts at that line.
+pub fn table(i: &str) -> IResult<&str, Vec<Line<'_>>> {
+    let mut lines = Vec::new();
+    let mut input = i;
+    while let Ok((rest, parsed)) = line(input) {
+        lines.push(parsed);
+        input = rest;
+    }
+    Ok((input, lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "translation")]
+    use enumset::enum_set;
+    use nom::error::ErrorKind;
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn rule_line_test() {
+        assert_eq!(
+            rule_line("joinword haha 123\n"),
+            Ok(("", Line::Rule { rule: Rule::Joinword { word: "haha",
+							dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				 comment: "" })));
+        assert_eq!(
+            rule_line("largesign அஇ 123\n"),
+            Ok(("", Line::Rule { rule: Rule::Largesign { word: "அஇ",
+							 dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				 comment: "" })));
+        assert_eq!(
+            rule_line("syllable haha 123\n"),
+            Ok(("", Line::Rule { rule: Rule::Syllable { word: "haha",
+							dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				 comment: "" })));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn rule_display_test() {
+        let (_, rule) = display("noback nocross display haha 21").unwrap();
+        assert_eq!(rule.to_string(), "noback nocross display haha 12");
+        let (_, rule) = space("space . 0").unwrap();
+        assert_eq!(rule.to_string(), "space . 0");
+    }
+
+    #[test]
+    #[cfg(feature = "emphasis")]
+    fn emphasis_display_test() {
+        let (_, rule) = endcapsphrase("endcapsphrase after 45-1f").unwrap();
+        assert_eq!(rule.to_string(), "endcapsphrase after 45-1f");
+        let (_, rule) = lencapsphrase("lencapsphrase 4").unwrap();
+        assert_eq!(rule.to_string(), "lencapsphrase 4");
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn lower_uplow_test() {
+        let (_, rule) = uplow("uplow Aa 17,1").unwrap();
+        assert_eq!(rule.lower_uplow().map(|rules| rules.map(|rule| rule.to_string())),
+                   Some(["uppercase A 17".to_string(), "lowercase a 1".to_string()]));
+        let (_, rule) = uplow("uplow Ää 345").unwrap();
+        assert_eq!(rule.lower_uplow().map(|rules| rules.map(|rule| rule.to_string())),
+                   Some(["uppercase Ä 345".to_string(), "lowercase ä 345".to_string()]));
+        assert_eq!(Rule::Include { filename: "foo.tbl" }.lower_uplow(), None);
+        let (_, rule) = uplow("uplow Aa 17,1").unwrap();
+        let [upper, lower] = rule.lower_uplow().unwrap();
+        assert_eq!(rule.char_definitions(), [upper.char_definitions(), lower.char_definitions()].concat());
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn line_display_test() {
+        let (_, lines) = table("# a comment\n   \nnocross display  haha 21  end \ndigit 1 1\n").unwrap();
+        let printed: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(printed, vec!["# a comment", "", "nocross display haha 12 end ", "digit 1 1"]);
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn complete_rule_test() {
+        assert_eq!(complete_rule("joinword haha 123 "),
+                   Ok(Rule::Joinword { word: "haha", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] }));
+        assert_eq!(complete_rule("joinword haha 123 comment"),
+                   Err(Err::Error(Error::new("comment", ErrorKind::Eof))));
+    }
+
+    #[test]
+    fn empty_line_test() {
+        assert_eq!(
+            empty_line("       \n"),
+            Ok(("", Line::Empty)));
+        assert_eq!(
+            empty_line("\n"),
+            Ok(("", Line::Empty)));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn table_failure_test() {
+        assert_eq!(table("display a 1\ndisplay b\njoinword to 235\n").map(|(rest, lines)| (rest, lines.len())),
+                   Ok(("display b\njoinword to 235\n", 1)));
+        assert_eq!(table("display a 1\nfoo b\n").map(|(rest, lines)| (rest, lines.len())),
+                   Ok(("foo b\n", 1)));
+    }
+
+    #[test]
+    fn comment_line_test() {
+        assert_eq!(
+            comment_line("# haha 1234    \n"),
+            Ok(("", Line::Comment { comment: " haha 1234    "})));
+        assert_eq!(
+            comment_line("# haha 1234    "),
+            Err(Err::Error(Error::new("", ErrorKind::CrLf))));
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn end_comment_test() {
+	assert_eq!(
+	    end_comment("an end comment\n"),
+	    Err(Err::Error(Error::new("an end comment\n", ErrorKind::Space))));
+	assert_eq!(end_comment(" an end comment\n"), Ok(("\n", "an end comment")));
+        assert_eq!(
+            rule_line("joinword haha 123 comment \n"),
+            Ok(("", Line::Rule { rule: Rule::Joinword { word: "haha",
+							dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				 comment: "comment " })));
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn table_test() {
+        assert_eq!(
+            table(concat!("       \n",
+			  "joinword haha 123\n",
+			  "syllable haha 123-1f\n")),
+            Ok(("", vec![Line::Empty,
+			 Line::Rule { rule: Rule::Joinword { word: "haha",
+							     dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				      comment: "" },
+			 Line::Rule { rule: Rule::Syllable { word: "haha",
+							     dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3,
+									BrailleDot::DOT1 | BrailleDot::DOTF] },
+				      comment: "" }])));
+        assert_eq!(
+            table(concat!("       \n",
+			  "# just testing\n",
+			  "nocross multind hehe 123\n",
+			  "joinword haha 123\n",
+			  "syllable haha 123\n")),
+            Ok(("", vec![Line::Empty,
+			 Line::Comment { comment: " just testing" },
+			 Line::Rule { rule: Rule::Multind { chars: "hehe",
+							    dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3],
+							    prefixes: enum_set!(Prefix::Nocross) },
+				      comment: "" },
+			 Line::Rule { rule: Rule::Joinword { word: "haha",
+							     dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				      comment: "" },
+			 Line::Rule { rule: Rule::Syllable { word: "haha",
+							     dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] },
+				      comment: "" }])));
+    }
+}
@@ -0,0 +1,268 @@
+//! Opcodes that define characters.
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::one_of;
+use nom::character::complete::space1;
+use nom::combinator::fail;
+use nom::combinator::opt;
+use nom::combinator::recognize;
+use nom::combinator::verify;
+use nom::sequence::preceded;
+use nom::sequence::tuple;
+
+use nom::IResult;
+use nom_unicode::complete::digit1 as unicode_digit1;
+
+use crate::opcode::Opcode;
+use crate::parser::*;
+
+pub fn undefined(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("undefined"), space1, dots))(i)?;
+    Ok((input, Rule::Undefined { dots }))
+}
+
+pub fn display(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("display"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Display { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn space(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, c, _, dots)) = tuple((opt(prefixes), tag("space"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Space { ch: c, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn punctuation(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, c, _, dots)) = tuple((opt(prefixes), tag("punctuation"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Punctuation { ch: c, dots, prefixes: prefixes.unwrap() }))
+}
+
+/// Parses `digit`, whose character operand has to be a decimal digit,
+/// see `is_decimal_digit`.
+pub fn digit(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, digit, _, dots)) = tuple((tag("digit"), space1, verify(single_char, |c| is_decimal_digit(*c)), space1, dots))(i)?;
+    Ok((input, Rule::Digit { ch: digit, dots }))
+}
+
+/// Parses `litdigit`, which defines the dots of digits in literary
+/// context, as opposed to `digit` for computer braille.
+pub fn litdigit(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars, _, dots)) = tuple((tag("litdigit"), space1, unicode_digit1, space1, dots))(i)?;
+    Ok((input, Rule::Litdigit { chars, dots }))
+}
+
+/// Parses the deprecated `uplow`, which defines an uppercase and a
+/// lowercase letter, optionally with different dots.
+pub fn uplow(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, upper, lower, _, dots, lower_dots)) =
+        tuple((tag("uplow"), space1, single_char, single_char, space1, dots, opt(preceded(tag(","), dots))))(i)?;
+    Ok((input, Rule::Uplow { upper, lower, dots, lower_dots }))
+}
+
+pub fn letter(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("letter"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Letter { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn uppercase(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("uppercase"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Uppercase { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn lowercase(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("lowercase"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Lowercase { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn sign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("sign"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Sign { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn math(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("math"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Math { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn base(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, attribute, _, derived, _, base)) =
+        tuple((tag("base"), space1, name, space1, single_char, space1, single_char))(i)?;
+    Ok((input, Rule::Base { attribute, derived, base }))
+}
+
+/// The name of a user defined character class or one of the numbered
+/// classes `0` to `7`.
+fn attribute_name(i: &str) -> IResult<&str, &str> {
+    alt((name, recognize(one_of("01234567"))))(i)
+}
+
+pub fn attribute(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, chars)) = tuple((tag("attribute"), space1, attribute_name, space1, chars))(i)?;
+    Ok((input, Rule::Attribute { name, chars }))
+}
+
+/// Parses the deprecated `class`, the predecessor of `attribute`.
+pub fn class(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, chars)) = tuple((tag("class"), space1, name, space1, chars))(i)?;
+    Ok((input, Rule::Class { name, chars }))
+}
+
+/// Parses a rule of this family with the given opcode.
+pub fn rule(opcode: Opcode, i: &str) -> IResult<&str, Rule<'_>> {
+    match opcode {
+        Opcode::Undefined => undefined(i),
+        Opcode::Display => display(i),
+        Opcode::Space => space(i),
+        Opcode::Punctuation => punctuation(i),
+        Opcode::Digit => digit(i),
+        Opcode::Litdigit => litdigit(i),
+        Opcode::Uplow => uplow(i),
+        Opcode::Letter => letter(i),
+        Opcode::Uppercase => uppercase(i),
+        Opcode::Lowercase => lowercase(i),
+        Opcode::Sign => sign(i),
+        Opcode::Math => math(i),
+        Opcode::Base => base(i),
+        Opcode::Attribute => attribute(i),
+        Opcode::Class => class(i),
+        _ => fail(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enumset::enum_set;
+    use nom::error::{Error, ErrorKind};
+    use nom::Err;
+
+    #[test]
+    fn undefined_test() {
+        assert_eq!(undefined("undefined 12"), Ok(("", Rule::Undefined { dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2] })));
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(display("display haha 122"), Ok(("", Rule::Display { chars: "haha",
+									dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2],
+									prefixes: Prefixes::empty() })));
+    }
+
+    #[test]
+    fn space_test() {
+        assert_eq!(space("space . 0"),
+		   Ok(("", Rule::Space { ch: '.', dots: vec![enum_set!(BrailleDot::DOT0)], prefixes: Prefixes::empty() })));
+        assert_eq!(space("space \\s 0"),
+		   Ok(("", Rule::Space { ch: ' ', dots: vec![enum_set!(BrailleDot::DOT0)], prefixes: Prefixes::empty() })));
+        assert_eq!(space("space \\t 0").map(|(_, rule)| rule.to_string()), Ok("space \\t 0".to_string()));
+    }
+
+    #[test]
+    fn punctuation_test() {
+        assert_eq!(punctuation("punctuation . 46"),
+		   Ok(("", Rule::Punctuation { ch: '.',
+					       dots: vec![BrailleDot::DOT4 | BrailleDot::DOT6],
+					       prefixes: Prefixes::empty() })));
+        assert_eq!(punctuation("nofor punctuation « 236"),
+		   Ok(("", Rule::Punctuation { ch: '«',
+					       dots: vec![BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT6],
+					       prefixes: enum_set!(Prefix::Nofor) })));
+        assert_eq!(punctuation("punctuation # 3456").map(|(_, rule)| rule.to_string()), Ok("punctuation # 3456".to_string()));
+        assert!(punctuation("punctuation ,; 2").is_err());
+    }
+
+    #[test]
+    fn digit_test() {
+        assert_eq!(digit("digit 1 278"),
+		   Ok(("", Rule::Digit { ch: '1',
+					 dots: vec![BrailleDot::DOT2 | BrailleDot::DOT7 | BrailleDot::DOT8] })));
+        assert_eq!(digit("digit a 1"), Err(Err::Error(Error::new("a 1", ErrorKind::Verify))));
+        assert_eq!(digit("digit ½ 1"), Err(Err::Error(Error::new("½ 1", ErrorKind::Verify))));
+        assert_eq!(digit("digit ۲ 1278"),
+		   Ok(("", Rule::Digit { ch: '۲',
+					 dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT7 | BrailleDot::DOT8] })));
+    }
+
+    #[test]
+    fn litdigit_test() {
+        assert_eq!(litdigit("litdigit 0 245"),
+		   Ok(("", Rule::Litdigit { chars: "0", dots: vec![BrailleDot::DOT2 | BrailleDot::DOT4 | BrailleDot::DOT5] })));
+        assert_eq!(litdigit("litdigit ٠ 245"),
+		   Ok(("", Rule::Litdigit { chars: "٠", dots: vec![BrailleDot::DOT2 | BrailleDot::DOT4 | BrailleDot::DOT5] })));
+        assert!(litdigit("litdigit x 1").is_err());
+    }
+
+    #[test]
+    fn uplow_test() {
+        assert_eq!(uplow("uplow Aa 17,1"),
+                   Ok(("", Rule::Uplow { upper: 'A', lower: 'a', dots: vec![BrailleDot::DOT1 | BrailleDot::DOT7],
+                                         lower_dots: Some(vec![enum_set!(BrailleDot::DOT1)]) })));
+        assert_eq!(uplow("uplow Ää 345"),
+                   Ok(("", Rule::Uplow { upper: 'Ä', lower: 'ä', dots: vec![BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5],
+                                         lower_dots: None })));
+        assert!(uplow("uplow A 1").is_err());
+    }
+
+    #[test]
+    fn letter_test() {
+        assert_eq!(letter("letter a 1"),
+                   Ok(("", Rule::Letter { ch: 'a', dots: vec![enum_set!(BrailleDot::DOT1)], prefixes: Prefixes::empty() })));
+        assert_eq!(letter("nofor letter அ 1-23"),
+                   Ok(("", Rule::Letter { ch: 'அ', dots: vec![enum_set!(BrailleDot::DOT1), BrailleDot::DOT2 | BrailleDot::DOT3],
+                                          prefixes: enum_set!(Prefix::Nofor) })));
+        assert!(letter("letter ab 1").is_err());
+    }
+
+    #[test]
+    fn uppercase_test() {
+        assert_eq!(uppercase("uppercase A 17"),
+                   Ok(("", Rule::Uppercase { ch: 'A', dots: vec![BrailleDot::DOT1 | BrailleDot::DOT7], prefixes: Prefixes::empty() })));
+        assert_eq!(uppercase("noback uppercase A 17"),
+                   Ok(("", Rule::Uppercase { ch: 'A', dots: vec![BrailleDot::DOT1 | BrailleDot::DOT7], prefixes: enum_set!(Prefix::Noback) })));
+    }
+
+    #[test]
+    fn lowercase_test() {
+        assert_eq!(lowercase("lowercase ö 246"),
+                   Ok(("", Rule::Lowercase { ch: 'ö', dots: vec![BrailleDot::DOT2 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(lowercase("noback lowercase ö 246"),
+                   Ok(("", Rule::Lowercase { ch: 'ö', dots: vec![BrailleDot::DOT2 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Noback) })));
+    }
+
+    #[test]
+    fn sign_test() {
+        assert_eq!(sign("sign % 46-356"),
+                   Ok(("", Rule::Sign { ch: '%', dots: vec![BrailleDot::DOT4 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT5 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(sign("noback sign % 46-356"),
+                   Ok(("", Rule::Sign { ch: '%', dots: vec![BrailleDot::DOT4 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT5 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Noback) })));
+    }
+
+    #[test]
+    fn math_test() {
+        assert_eq!(math("math + 346"),
+                   Ok(("", Rule::Math { ch: '+', dots: vec![BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(math("noback math + 346"),
+                   Ok(("", Rule::Math { ch: '+', dots: vec![BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Noback) })));
+    }
+
+    #[test]
+    fn base_test() {
+        assert_eq!(base("base uppercase A a"), Ok(("", Rule::Base { attribute: "uppercase", derived: 'A', base: 'a' })));
+        assert_eq!(base("base uppercase \\ a").map(|(_, rule)| rule.to_string()), Ok("base uppercase \\\\ a".to_string()));
+        assert!(base("base uppercase AB a").is_err());
+    }
+
+    #[test]
+    fn attribute_test() {
+        assert_eq!(attribute("attribute vowels aeiou"), Ok(("", Rule::Attribute { name: "vowels", chars: "aeiou" })));
+        assert_eq!(attribute("attribute 0 äöü"), Ok(("", Rule::Attribute { name: "0", chars: "äöü" })));
+        assert!(attribute("attribute 8 xyz").is_err());
+        assert!(attribute("attribute vowels").is_err());
+    }
+
+    #[test]
+    fn class_test() {
+        assert_eq!(class("class vowels aeiou"), Ok(("", Rule::Class { name: "vowels", chars: "aeiou" })));
+        assert!(class("class 0 aeiou").is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! Opcodes for emphasis, capitalization and other indicators.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::fail;
+use nom::combinator::opt;
+use nom::sequence::tuple;
+
+use nom::IResult;
+
+use crate::opcode::Opcode;
+use crate::parser::*;
+
+/// Parses `emphclass`, which defines the name of an emphasis class
+/// for the mode opcodes such as `modeletter`.
+pub fn emphclass(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name)) = tuple((tag("emphclass"), space1, name))(i)?;
+    Ok((input, Rule::Emphclass { name }))
+}
+
+pub fn modeletter(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("modeletter"), space1, ascii_chars, space1, dots))(i)?;
+    Ok((input, Rule::Modeletter { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn capsletter(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, dots)) = tuple((opt(prefixes), tag("capsletter"), space1, dots))(i)?;
+    Ok((input, Rule::Capsletter { dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn begmodeword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("begmodeword"), space1, ascii_chars, space1, dots))(i)?;
+    Ok((input, Rule::Begmodeword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn begcapsword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, dots)) = tuple((opt(prefixes), tag("begcapsword"), space1, dots))(i)?;
+    Ok((input, Rule::Begcapsword { dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn endcapsword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, dots)) = tuple((opt(prefixes), tag("endcapsword"), space1, dots))(i)?;
+    Ok((input, Rule::Endcapsword { dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn capsmodechars(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("capsmodechars"), space1, chars))(i)?;
+    Ok((input, Rule::Capsmodechars { chars }))
+}
+
+pub fn begcaps(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("begcaps"), space1, dots))(i)?;
+    Ok((input, Rule::Begcaps { dots }))
+}
+
+pub fn endcaps(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("endcaps"), space1, dots))(i)?;
+    Ok((input, Rule::Endcaps { dots }))
+}
+
+pub fn begcapsphrase(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("begcapsphrase"), space1, dots))(i)?;
+    Ok((input, Rule::Begcapsphrase { dots }))
+}
+
+pub fn endcapsphrase(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, position, _, dots)) = tuple((tag("endcapsphrase"), space1, before_or_after, space1, dots))(i)?;
+    Ok((input, Rule::Endcapsphrase { dots, position }))
+}
+
+pub fn lencapsphrase(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, length)) = tuple((tag("lencapsphrase"), space1, number))(i)?;
+    Ok((input, Rule::Lencapsphrase { length }))
+}
+
+/// Parses `capsnocont`, which has no operands.
+pub fn capsnocont(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, _) = tag("capsnocont")(i)?;
+    Ok((input, Rule::Capsnocont))
+}
+
+pub fn letsign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("letsign"), space1, dots))(i)?;
+    Ok((input, Rule::Letsign { dots }))
+}
+
+pub fn noletsign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("noletsign"), space1, chars))(i)?;
+    Ok((input, Rule::Noletsign { chars }))
+}
+
+pub fn noletsignbefore(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("noletsignbefore"), space1, chars))(i)?;
+    Ok((input, Rule::Noletsignbefore { chars }))
+}
+
+pub fn noletsignafter(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("noletsignafter"), space1, chars))(i)?;
+    Ok((input, Rule::Noletsignafter { chars }))
+}
+
+pub fn nocontractsign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("nocontractsign"), space1, dots))(i)?;
+    Ok((input, Rule::Nocontractsign { dots }))
+}
+
+pub fn numsign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("numsign"), space1, dots))(i)?;
+    Ok((input, Rule::Numsign { dots }))
+}
+
+pub fn nonumsign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("nonumsign"), space1, dots))(i)?;
+    Ok((input, Rule::Nonumsign { dots }))
+}
+
+pub fn numericmodechars(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("numericmodechars"), space1, chars))(i)?;
+    Ok((input, Rule::Numericmodechars { chars }))
+}
+
+/// Parses a rule of this family with the given opcode.
+pub fn rule(opcode: Opcode, i: &str) -> IResult<&str, Rule<'_>> {
+    match opcode {
+        Opcode::Emphclass => emphclass(i),
+        Opcode::Modeletter => modeletter(i),
+        Opcode::Capsletter => capsletter(i),
+        Opcode::Begmodeword => begmodeword(i),
+        Opcode::Begcapsword => begcapsword(i),
+        Opcode::Endcapsword => endcapsword(i),
+        Opcode::Capsmodechars => capsmodechars(i),
+        Opcode::Begcaps => begcaps(i),
+        Opcode::Endcaps => endcaps(i),
+        Opcode::Begcapsphrase => begcapsphrase(i),
+        Opcode::Endcapsphrase => endcapsphrase(i),
+        Opcode::Lencapsphrase => lencapsphrase(i),
+        Opcode::Capsnocont => capsnocont(i),
+        Opcode::Letsign => letsign(i),
+        Opcode::Noletsign => noletsign(i),
+        Opcode::Noletsignbefore => noletsignbefore(i),
+        Opcode::Noletsignafter => noletsignafter(i),
+        Opcode::Nocontractsign => nocontractsign(i),
+        Opcode::Numsign => numsign(i),
+        Opcode::Nonumsign => nonumsign(i),
+        Opcode::Numericmodechars => numericmodechars(i),
+        _ => fail(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interop::RuleSource;
+    use enumset::enum_set;
+    use nom::error::Error;
+    use nom::error::ErrorKind;
+    use nom::Err;
+
+    #[test]
+    fn modeletter_test() {
+        assert_eq!(modeletter("modeletter uppercase 6"),
+		   Ok(("", Rule::Modeletter { chars: "uppercase",
+					      dots: vec![enum_set!(BrailleDot::DOT6)],
+					      prefixes: Prefixes::empty()})));
+    }
+
+    #[test]
+    fn capsletter_test() {
+        assert_eq!(capsletter("capsletter 6"),
+		   Ok(("", Rule::Capsletter { dots: vec![enum_set!(BrailleDot::DOT6)],
+					      prefixes: Prefixes::empty()})));
+        assert_eq!(capsletter("noback capsletter 6"),
+		   Ok(("", Rule::Capsletter { dots: vec![enum_set!(BrailleDot::DOT6)],
+					      prefixes: enum_set!(Prefix::Noback)})));
+        assert_eq!(capsletter("nofor capsletter 6"),
+		   Ok(("", Rule::Capsletter { dots: vec![enum_set!(BrailleDot::DOT6)],
+					      prefixes: enum_set!(Prefix::Nofor)})));
+        assert!(capsletter("capsletter x").is_err());
+    }
+
+    #[test]
+    fn begmodeword_test() {
+        assert_eq!(begmodeword("begmodeword uppercase 6"),
+		   Ok(("", Rule::Begmodeword { chars: "uppercase",
+					       dots: vec![enum_set!(BrailleDot::DOT6)],
+					       prefixes: Prefixes::empty()})));
+    }
+
+    #[test]
+    fn emphclass_test() {
+        assert_eq!(emphclass("emphclass italic"), Ok(("", Rule::Emphclass { name: "italic" })));
+        assert_eq!(rule(Opcode::Emphclass, "emphclass underline").map(|(_, rule)| rule.to_string()),
+		   Ok("emphclass underline".to_string()));
+        assert!(emphclass("emphclass").is_err());
+    }
+
+    #[test]
+    fn begcapsword_test() {
+        assert_eq!(begcapsword("begcapsword 6-6"),
+		   Ok(("", Rule::Begcapsword { dots: vec![enum_set!(BrailleDot::DOT6),
+							  enum_set!(BrailleDot::DOT6)],
+					       prefixes: Prefixes::empty()})));
+        assert_eq!(rule(Opcode::Begcapsword, "nocross begcapsword ⠠⠠"),
+		   Ok(("", Rule::Begcapsword { dots: vec![enum_set!(BrailleDot::DOT6),
+							  enum_set!(BrailleDot::DOT6)],
+					       prefixes: enum_set!(Prefix::Nocross)})));
+    }
+
+    #[test]
+    fn endcapsword_test() {
+        assert_eq!(endcapsword("endcapsword 6-3"),
+		   Ok(("", Rule::Endcapsword { dots: vec![enum_set!(BrailleDot::DOT6),
+							  enum_set!(BrailleDot::DOT3)],
+					       prefixes: Prefixes::empty()})));
+        let (_, lines) = table("begcapsword 6-6\nendcapsword 6-3\n").unwrap();
+        assert_eq!(lines.rules().iter().map(|rule| rule.opcode()).collect::<Vec<_>>(),
+                   vec![Opcode::Begcapsword, Opcode::Endcapsword]);
+        assert!(endcapsword("endcapsword").is_err());
+    }
+
+    #[test]
+    fn capsmodechars_test() {
+        assert_eq!(capsmodechars("capsmodechars -/"),
+		   Ok(("", Rule::Capsmodechars { chars: "-/"})));
+        assert_eq!(rule(Opcode::Capsmodechars, "capsmodechars '\\x2019").map(|(_, rule)| rule.to_string()),
+		   Ok("capsmodechars '\\x2019".to_string()));
+        assert!(capsmodechars("capsmodechars").is_err());
+    }
+
+    #[test]
+    fn begcaps_test() {
+        assert_eq!(begcaps("begcaps 6-6-6"),
+		   Ok(("", Rule::Begcaps { dots: vec![enum_set!(BrailleDot::DOT6),
+						      enum_set!(BrailleDot::DOT6),
+						      enum_set!(BrailleDot::DOT6)]})));
+    }
+
+    #[test]
+    fn endcaps_test() {
+        assert_eq!(endcaps("endcaps 6-3"),
+		   Ok(("", Rule::Endcaps { dots: vec![enum_set!(BrailleDot::DOT6),
+							  enum_set!(BrailleDot::DOT3)]})));
+        assert_eq!(endcaps("endcaps 6-3").map(|(_, rule)| rule.to_string()), Ok("endcaps 6-3".to_string()));
+        assert!(matches!(crate::parser::rule("noback endcaps 6-3"), Err(Err::Failure(_))));
+        assert!(matches!(crate::parser::rule("nofor begcaps 6-6-6"), Err(Err::Failure(_))));
+    }
+
+    #[test]
+    fn begcapsphrase_test() {
+        assert_eq!(begcapsphrase("begcapsphrase 45-45"),
+		   Ok(("", Rule::Begcapsphrase { dots: vec![enum_set!(BrailleDot::DOT4 | BrailleDot::DOT5),
+							    enum_set!(BrailleDot::DOT4 | BrailleDot::DOT5)]})));
+        assert_eq!(rule(Opcode::Begcapsphrase, "begcapsphrase 45-45").map(|(_, rule)| rule.opcode()),
+		   Ok(Opcode::Begcapsphrase));
+    }
+
+    #[test]
+    fn endcapsphrase_test() {
+        assert_eq!(endcapsphrase("endcapsphrase before 45"),
+		   Ok(("", Rule::Endcapsphrase { dots: vec![BrailleDot::DOT4 | BrailleDot::DOT5],
+						 position: Position::Before})));
+        assert_eq!(endcapsphrase("endcapsphrase after 45"),
+		   Ok(("", Rule::Endcapsphrase { dots: vec![BrailleDot::DOT4 | BrailleDot::DOT5],
+						 position: Position::After})));
+        assert_eq!(endcapsphrase("endcapsphrase foo 45"),
+		   Err(Err::Error(Error::new("foo 45", ErrorKind::Tag))));
+        assert_eq!(rule(Opcode::Endcapsphrase, "endcapsphrase after 45").map(|(_, rule)| rule.opcode()),
+		   Ok(Opcode::Endcapsphrase));
+        assert!(endcapsphrase("endcapsphrase 45").is_err());
+    }
+
+    #[test]
+    fn lencapsphrase_test() {
+        assert_eq!(lencapsphrase("lencapsphrase 4"),
+		   Ok(("", Rule::Lencapsphrase { length: 4 })));
+        assert_eq!(crate::parser::rule("lencapsphrase 4"), Ok(("", Rule::Lencapsphrase { length: 4 })));
+        assert!(lencapsphrase("lencapsphrase four").is_err());
+        assert!(lencapsphrase("lencapsphrase 256").is_err());
+    }
+
+    #[test]
+    fn capsnocont_test() {
+        assert_eq!(capsnocont("capsnocont"), Ok(("", Rule::Capsnocont)));
+        assert_eq!(rule_line("capsnocont\n"), Ok(("", Line::Rule { rule: Rule::Capsnocont, comment: "" })));
+        assert_eq!(rule_line("capsnocont  no contractions in caps\n"),
+                   Ok(("", Line::Rule { rule: Rule::Capsnocont, comment: "no contractions in caps" })));
+    }
+
+    #[test]
+    fn letsign_test() {
+        assert_eq!(letsign("letsign 56"), Ok(("", Rule::Letsign { dots: vec![BrailleDot::DOT5 | BrailleDot::DOT6] })));
+        assert!(letsign("letsign").is_err());
+    }
+
+    #[test]
+    fn noletsign_test() {
+        assert_eq!(noletsign("noletsign aio"), Ok(("", Rule::Noletsign { chars: "aio" })));
+        assert!(noletsign("noletsign").is_err());
+    }
+
+    #[test]
+    fn noletsignbefore_test() {
+        assert_eq!(noletsignbefore("noletsignbefore $"), Ok(("", Rule::Noletsignbefore { chars: "$" })));
+        assert!(noletsignbefore("noletsignbefore").is_err());
+    }
+
+    #[test]
+    fn noletsignafter_test() {
+        assert_eq!(noletsignafter("noletsignafter '"), Ok(("", Rule::Noletsignafter { chars: "'" })));
+        assert!(noletsignafter("noletsignafter").is_err());
+    }
+
+    #[test]
+    fn nocontractsign_test() {
+        assert_eq!(nocontractsign("nocontractsign 56"), Ok(("", Rule::Nocontractsign { dots: vec![BrailleDot::DOT5 | BrailleDot::DOT6] })));
+        assert!(nocontractsign("nocontractsign").is_err());
+    }
+
+    #[test]
+    fn numsign_test() {
+        assert_eq!(numsign("numsign 3456"), Ok(("", Rule::Numsign { dots: vec![BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5 | BrailleDot::DOT6] })));
+        assert!(numsign("numsign").is_err());
+    }
+
+    #[test]
+    fn nonumsign_test() {
+        assert_eq!(nonumsign("nonumsign 56"), Ok(("", Rule::Nonumsign { dots: vec![BrailleDot::DOT5 | BrailleDot::DOT6] })));
+        assert!(nonumsign("nonumsign").is_err());
+        let (_, lines) = table("numsign 3456\nnonumsign 56\n").unwrap();
+        assert_eq!(lines.rules().iter().map(|rule| rule.opcode()).collect::<Vec<_>>(),
+                   vec![Opcode::Numsign, Opcode::Nonumsign]);
+    }
+
+    #[test]
+    fn numericmodechars_test() {
+        assert_eq!(numericmodechars("numericmodechars .,"), Ok(("", Rule::Numericmodechars { chars: ".," })));
+        assert!(numericmodechars("numericmodechars").is_err());
+    }
+}
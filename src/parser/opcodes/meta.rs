@@ -0,0 +1,34 @@
+//! Opcodes that are about the table itself.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::fail;
+use nom::sequence::tuple;
+
+use nom::IResult;
+
+use crate::opcode::Opcode;
+use crate::parser::*;
+
+pub fn include(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, filename)) = tuple((tag("include"), space1, filename))(i)?;
+    Ok((input, Rule::Include { filename }))
+}
+
+/// Parses a rule of this family with the given opcode.
+pub fn rule(opcode: Opcode, i: &str) -> IResult<&str, Rule<'_>> {
+    match opcode {
+        Opcode::Include => include(i),
+        _ => fail(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_test() {
+        assert_eq!(include("include filename.tbl"), Ok(("", Rule::Include { filename: "filename.tbl" })));
+    }
+}
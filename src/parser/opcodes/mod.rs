@@ -0,0 +1,129 @@
+//! The parsers for the opcodes, one module per opcode family.
+//!
+//! Each family is only available if the cargo feature of the same name
+//! is enabled. The module of a disabled family is replaced by a stub
+//! that rejects every rule.
+//!
+//! Rules are not parsed by trying every opcode in turn. Instead the
+//! keyword is read first and the rule is handed to the parser of that
+//! opcode, so a line is only parsed once, no matter how many opcodes
+//! there are.
+
+use nom::bytes::complete::take_till1;
+use nom::combinator::{cut, fail};
+use nom::IResult;
+
+use crate::opcode::{Category, Opcode};
+use crate::parser::{prefixes, Rule};
+
+#[cfg(feature = "meta")]
+pub mod meta;
+#[cfg(not(feature = "meta"))]
+mod meta {
+    pub fn rule(_: crate::opcode::Opcode, i: &str) -> nom::IResult<&str, crate::parser::Rule<'_>> {
+        nom::combinator::fail(i)
+    }
+}
+
+#[cfg(feature = "chardef")]
+pub mod chardef;
+#[cfg(not(feature = "chardef"))]
+mod chardef {
+    pub fn rule(_: crate::opcode::Opcode, i: &str) -> nom::IResult<&str, crate::parser::Rule<'_>> {
+        nom::combinator::fail(i)
+    }
+}
+
+#[cfg(feature = "translation")]
+pub mod translation;
+#[cfg(not(feature = "translation"))]
+mod translation {
+    pub fn rule(_: crate::opcode::Opcode, i: &str) -> nom::IResult<&str, crate::parser::Rule<'_>> {
+        nom::combinator::fail(i)
+    }
+}
+
+#[cfg(feature = "emphasis")]
+pub mod emphasis;
+#[cfg(not(feature = "emphasis"))]
+mod emphasis {
+    pub fn rule(_: crate::opcode::Opcode, i: &str) -> nom::IResult<&str, crate::parser::Rule<'_>> {
+        nom::combinator::fail(i)
+    }
+}
+
+#[cfg(feature = "multipass")]
+pub mod multipass;
+#[cfg(not(feature = "multipass"))]
+mod multipass {
+    pub fn rule(_: crate::opcode::Opcode, i: &str) -> nom::IResult<&str, crate::parser::Rule<'_>> {
+        nom::combinator::fail(i)
+    }
+}
+
+/// Whether the cargo feature of an opcode family is enabled.
+pub(crate) fn enabled(category: Category) -> bool {
+    match category {
+        Category::Meta => cfg!(feature = "meta"),
+        Category::CharDef => cfg!(feature = "chardef"),
+        Category::Translation => cfg!(feature = "translation"),
+        Category::Emphasis => cfg!(feature = "emphasis"),
+        Category::Multipass => cfg!(feature = "multipass"),
+    }
+}
+
+/// Reads the opcode keyword of a rule, skipping any prefixes, without
+/// consuming input. Opcodes of disabled families are rejected like
+/// unknown keywords.
+fn opcode(i: &str) -> IResult<&str, Opcode> {
+    let (rest, _) = prefixes(i)?;
+    let (_, keyword) = take_till1(char::is_whitespace)(rest)?;
+    match Opcode::from_keyword(keyword) {
+        Some(opcode) if enabled(opcode.category()) => Ok((i, opcode)),
+        _ => fail(rest),
+    }
+}
+
+/// Parses a rule of any of the opcode families enabled by cargo
+/// features.
+///
+/// Once the keyword is recognized the parser is committed to the
+/// opcode: invalid operands are reported as a `Failure` for that
+/// opcode instead of an `Error` that would let callers try other
+/// alternatives.
+pub fn rule(i: &str) -> IResult<&str, Rule<'_>> {
+    let (i, opcode) = opcode(i)?;
+    cut(move |i| match opcode.category() {
+        Category::Meta => meta::rule(opcode, i),
+        Category::CharDef => chardef::rule(opcode, i),
+        Category::Translation => translation::rule(opcode, i),
+        Category::Emphasis => emphasis::rule(opcode, i),
+        Category::Multipass => multipass::rule(opcode, i),
+    })(i)
+}
+
+#[cfg(all(test, feature = "meta", feature = "chardef", feature = "translation", feature = "emphasis"))]
+mod tests {
+    use super::*;
+    use nom::error::{Error, ErrorKind};
+    use nom::Err;
+
+    #[test]
+    fn opcode_test() {
+        assert_eq!(opcode("nocross display a 1"), Ok(("nocross display a 1", Opcode::Display)));
+        assert_eq!(opcode("joinword to 235"), Ok(("joinword to 235", Opcode::Joinword)));
+        assert_eq!(opcode("noback foo a 1"), Err(Err::Error(Error::new("foo a 1", ErrorKind::Fail))));
+        assert_eq!(opcode(""), Err(Err::Error(Error::new("", ErrorKind::TakeTill1))));
+    }
+
+    #[test]
+    fn rule_test() {
+        assert_eq!(rule("include foo.tbl"), Ok(("", Rule::Include { filename: "foo.tbl" })));
+        assert_eq!(rule("begcaps 6-6"), Ok(("", Rule::Begcaps { dots: crate::dots!["6", "6"] })));
+        // a known keyword with a broken operand does not fall back to
+        // other opcodes
+        assert_eq!(rule("display a"), Err(Err::Failure(Error::new("", ErrorKind::Space))));
+        assert_eq!(rule("endcapsphrase foo 45"), Err(Err::Failure(Error::new("foo 45", ErrorKind::Tag))));
+        assert_eq!(rule("foo a 1"), Err(Err::Error(Error::new("foo a 1", ErrorKind::Fail))));
+    }
+}
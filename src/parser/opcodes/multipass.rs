@@ -0,0 +1,151 @@
+//! Opcodes for the multipass translation stages.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::fail;
+use nom::combinator::opt;
+use nom::sequence::tuple;
+
+use nom::IResult;
+
+use crate::opcode::Opcode;
+use crate::parser::*;
+
+pub fn swapcd(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, chars, _, dots)) =
+        tuple((tag("swapcd"), space1, name, space1, chars, space1, dots_list))(i)?;
+    Ok((input, Rule::Swapcd { name, chars, dots }))
+}
+
+pub fn swapdd(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, from, _, to)) =
+        tuple((tag("swapdd"), space1, name, space1, dots_list, space1, dots_list))(i)?;
+    Ok((input, Rule::Swapdd { name, from, to }))
+}
+
+pub fn swapcc(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, from, _, to)) =
+        tuple((tag("swapcc"), space1, name, space1, chars, space1, chars))(i)?;
+    Ok((input, Rule::Swapcc { name, from, to }))
+}
+
+pub fn context(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, test, _, action)) =
+        tuple((opt(prefixes), tag("context"), space1, multipass_operand, space1, multipass_operand))(i)?;
+    Ok((input, Rule::Context { test, action, prefixes: prefixes.unwrap() }))
+}
+
+pub fn correct(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, test, _, action)) =
+        tuple((opt(prefixes), tag("correct"), space1, multipass_operand, space1, multipass_operand))(i)?;
+    Ok((input, Rule::Correct { test, action, prefixes: prefixes.unwrap() }))
+}
+
+pub fn pass2(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, test, _, action)) =
+        tuple((opt(prefixes), tag("pass2"), space1, multipass_operand, space1, multipass_operand))(i)?;
+    Ok((input, Rule::Pass2 { test, action, prefixes: prefixes.unwrap() }))
+}
+
+pub fn pass3(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, test, _, action)) =
+        tuple((opt(prefixes), tag("pass3"), space1, multipass_operand, space1, multipass_operand))(i)?;
+    Ok((input, Rule::Pass3 { test, action, prefixes: prefixes.unwrap() }))
+}
+
+pub fn pass4(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, test, _, action)) =
+        tuple((opt(prefixes), tag("pass4"), space1, multipass_operand, space1, multipass_operand))(i)?;
+    Ok((input, Rule::Pass4 { test, action, prefixes: prefixes.unwrap() }))
+}
+
+/// Parses a rule of this family with the given opcode.
+pub fn rule(opcode: Opcode, i: &str) -> IResult<&str, Rule<'_>> {
+    match opcode {
+        Opcode::Swapcd => swapcd(i),
+        Opcode::Swapdd => swapdd(i),
+        Opcode::Swapcc => swapcc(i),
+        Opcode::Context => context(i),
+        Opcode::Correct => correct(i),
+        Opcode::Pass2 => pass2(i),
+        Opcode::Pass3 => pass3(i),
+        Opcode::Pass4 => pass4(i),
+        _ => fail(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enumset::enum_set;
+
+    #[test]
+    fn swapcd_test() {
+        assert_eq!(swapcd("swapcd dropped 1234 2,23,25-2"),
+                   Ok(("", Rule::Swapcd { name: "dropped", chars: "1234",
+                                          dots: vec![vec![enum_set!(BrailleDot::DOT2)],
+                                                     vec![BrailleDot::DOT2 | BrailleDot::DOT3],
+                                                     vec![BrailleDot::DOT2 | BrailleDot::DOT5, enum_set!(BrailleDot::DOT2)]] })));
+        assert_eq!(swapcd("swapcd dropped 12 2,23").map(|(_, rule)| rule.to_string()), Ok("swapcd dropped 12 2,23".to_string()));
+    }
+
+    #[test]
+    fn swapdd_test() {
+        assert_eq!(swapdd("swapdd raise 2,23 1,12"),
+                   Ok(("", Rule::Swapdd { name: "raise",
+                                          from: vec![vec![enum_set!(BrailleDot::DOT2)], vec![BrailleDot::DOT2 | BrailleDot::DOT3]],
+                                          to: vec![vec![enum_set!(BrailleDot::DOT1)], vec![BrailleDot::DOT1 | BrailleDot::DOT2]] })));
+        assert!(swapdd("swapdd raise 2,23").is_err());
+    }
+
+    #[test]
+    fn swapcc_test() {
+        assert_eq!(swapcc("swapcc quotes \"' «‹"), Ok(("", Rule::Swapcc { name: "quotes", from: "\"'", to: "«‹" })));
+        assert!(swapcc("swapcc quotes \"'").is_err());
+    }
+
+    #[test]
+    fn context_test() {
+        assert_eq!(context("context _$l[$l] @123"),
+                   Ok(("", Rule::Context { test: "_$l[$l]", action: "@123", prefixes: Prefixes::empty() })));
+        assert_eq!(context("context \"a\"[] ?"),
+                   Ok(("", Rule::Context { test: "\"a\"[]", action: "?", prefixes: Prefixes::empty() })));
+        assert!(context("context _$l[$l]").is_err());
+    }
+
+    #[test]
+    fn correct_test() {
+        assert_eq!(correct("correct \"''\"[] \"\""),
+                   Ok(("", Rule::Correct { test: "\"''\"[]", action: "\"\"", prefixes: Prefixes::empty() })));
+        assert_eq!(correct("noback correct \"''\"[] \"\""),
+                   Ok(("", Rule::Correct { test: "\"''\"[]", action: "\"\"", prefixes: enum_set!(Prefix::Noback) })));
+        assert!(correct("correct \"''\"[]").is_err());
+    }
+
+    #[test]
+    fn pass2_test() {
+        assert_eq!(pass2("pass2 @1-2[]@3 @4"),
+                   Ok(("", Rule::Pass2 { test: "@1-2[]@3", action: "@4", prefixes: Prefixes::empty() })));
+        assert_eq!(pass2("noback pass2 @1-2[]@3 @4"),
+                   Ok(("", Rule::Pass2 { test: "@1-2[]@3", action: "@4", prefixes: enum_set!(Prefix::Noback) })));
+        assert!(pass2("pass2 @1-2[]@3").is_err());
+    }
+
+    #[test]
+    fn pass3_test() {
+        assert_eq!(pass3("pass3 [@56]$l @6"),
+                   Ok(("", Rule::Pass3 { test: "[@56]$l", action: "@6", prefixes: Prefixes::empty() })));
+        assert_eq!(pass3("noback pass3 [@56]$l @6"),
+                   Ok(("", Rule::Pass3 { test: "[@56]$l", action: "@6", prefixes: enum_set!(Prefix::Noback) })));
+        assert!(pass3("pass3 [@56]$l").is_err());
+    }
+
+    #[test]
+    fn pass4_test() {
+        assert_eq!(pass4("pass4 @3456[@3456] ?"),
+                   Ok(("", Rule::Pass4 { test: "@3456[@3456]", action: "?", prefixes: Prefixes::empty() })));
+        assert_eq!(pass4("noback pass4 @3456[@3456] ?"),
+                   Ok(("", Rule::Pass4 { test: "@3456[@3456]", action: "?", prefixes: enum_set!(Prefix::Noback) })));
+        assert!(pass4("pass4 @3456[@3456]").is_err());
+    }
+}
@@ -0,0 +1,432 @@
+//! Opcodes that define how characters and words are translated.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::fail;
+use nom::combinator::opt;
+use nom::sequence::preceded;
+use nom::sequence::tuple;
+
+use nom::IResult;
+
+use crate::opcode::Opcode;
+use crate::parser::*;
+
+pub fn multind(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("multind"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Multind { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn largesign(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, word, _, dots)) = tuple((
+        tag("largesign"), space1, chars, space1, dots,
+    ))(i)?;
+    Ok((input, Rule::Largesign { word, dots }))
+}
+
+pub fn syllable(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, word, _, dots)) = tuple((
+        tag("syllable"), space1, chars, space1, dots,
+    ))(i)?;
+    Ok((input, Rule::Syllable { word, dots }))
+}
+
+pub fn joinword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, word, _, dots)) = tuple((
+        tag("joinword"), space1, chars, space1, dots,
+    ))(i)?;
+    Ok((input, Rule::Joinword { word, dots }))
+}
+
+pub fn grouping(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, name, _, open, close, _, (open_dots, close_dots))) = tuple((
+        tag("grouping"), space1, name, space1, single_char, single_char, space1, dots_pair,
+    ))(i)?;
+    Ok((input, Rule::Grouping { name, open, close, open_dots, close_dots }))
+}
+
+pub fn r#match(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, pre, _, chars, _, post, _, dots)) = tuple((
+        opt(prefixes), tag("match"), space1, pattern, space1, chars, space1, pattern, space1, dots,
+    ))(i)?;
+    Ok((input, Rule::Match { pre, chars, post, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn backmatch(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, pre, _, chars, _, post, _, dots)) = tuple((
+        opt(prefixes), tag("backmatch"), space1, pattern, space1, chars, space1, pattern, space1, dots,
+    ))(i)?;
+    Ok((input, Rule::Backmatch { pre, chars, post, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn literal(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("literal"), space1, chars))(i)?;
+    Ok((input, Rule::Literal { chars }))
+}
+
+pub fn always(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("always"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Always { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn word(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("word"), space1, chars, space1, braille))(i)?;
+    Ok((input, Rule::Word { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn nocont(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("nocont"), space1, chars))(i)?;
+    Ok((input, Rule::Nocont { chars }))
+}
+
+pub fn compbrl(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("compbrl"), space1, chars))(i)?;
+    Ok((input, Rule::Compbrl { chars }))
+}
+
+pub fn comp6(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, ch, _, dots)) = tuple((opt(prefixes), tag("comp6"), space1, single_char, space1, dots))(i)?;
+    Ok((input, Rule::Comp6 { ch, dots, prefixes: prefixes.unwrap() }))
+}
+
+/// Parses `replace`, whose replacement is optional. Without it the
+/// characters are removed.
+pub fn replace(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, from, to)) = tuple((tag("replace"), space1, chars, opt(preceded(space1, chars))))(i)?;
+    Ok((input, Rule::Replace { from, to }))
+}
+
+pub fn repeated(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("repeated"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Repeated { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn repword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("repword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Repword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn rependword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, (dots, otherdots))) =
+        tuple((opt(prefixes), tag("rependword"), space1, chars, space1, dots_pair))(i)?;
+    Ok((input, Rule::Rependword { chars, dots, otherdots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn begword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("begword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Begword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn midword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("midword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Midword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn endword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("endword"), space1, chars, space1, braille))(i)?;
+    Ok((input, Rule::Endword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn partword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("partword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Partword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn begmidword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("begmidword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Begmidword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn midendword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("midendword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Midendword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn prfxword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("prfxword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Prfxword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn sufword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("sufword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Sufword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn lowword(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("lowword"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Lowword { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn prepunc(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("prepunc"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Prepunc { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn postpunc(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("postpunc"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Postpunc { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn begnum(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("begnum"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Begnum { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn midnum(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("midnum"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Midnum { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn endnum(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("endnum"), space1, chars, space1, braille))(i)?;
+    Ok((input, Rule::Endnum { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn joinnum(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (prefixes, _, _, chars, _, dots)) = tuple((opt(prefixes), tag("joinnum"), space1, chars, space1, dots))(i)?;
+    Ok((input, Rule::Joinnum { chars, dots, prefixes: prefixes.unwrap() }))
+}
+
+pub fn contraction(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, chars)) = tuple((tag("contraction"), space1, chars))(i)?;
+    Ok((input, Rule::Contraction { chars }))
+}
+
+/// Parses `exactdots`, whose dots are written with a leading `@`.
+pub fn exactdots(i: &str) -> IResult<&str, Rule<'_>> {
+    let (input, (_, _, dots)) = tuple((tag("exactdots"), space1, preceded(tag("@"), dots)))(i)?;
+    Ok((input, Rule::Exactdots { dots }))
+}
+
+/// Parses a rule of this family with the given opcode.
+pub fn rule(opcode: Opcode, i: &str) -> IResult<&str, Rule<'_>> {
+    match opcode {
+        Opcode::Multind => multind(i),
+        Opcode::Largesign => largesign(i),
+        Opcode::Syllable => syllable(i),
+        Opcode::Joinword => joinword(i),
+        Opcode::Grouping => grouping(i),
+        Opcode::Match => r#match(i),
+        Opcode::Backmatch => backmatch(i),
+        Opcode::Literal => literal(i),
+        Opcode::Always => always(i),
+        Opcode::Word => word(i),
+        Opcode::Nocont => nocont(i),
+        Opcode::Compbrl => compbrl(i),
+        Opcode::Comp6 => comp6(i),
+        Opcode::Replace => replace(i),
+        Opcode::Repeated => repeated(i),
+        Opcode::Repword => repword(i),
+        Opcode::Rependword => rependword(i),
+        Opcode::Begword => begword(i),
+        Opcode::Midword => midword(i),
+        Opcode::Endword => endword(i),
+        Opcode::Partword => partword(i),
+        Opcode::Begmidword => begmidword(i),
+        Opcode::Midendword => midendword(i),
+        Opcode::Prfxword => prfxword(i),
+        Opcode::Sufword => sufword(i),
+        Opcode::Lowword => lowword(i),
+        Opcode::Prepunc => prepunc(i),
+        Opcode::Postpunc => postpunc(i),
+        Opcode::Begnum => begnum(i),
+        Opcode::Midnum => midnum(i),
+        Opcode::Endnum => endnum(i),
+        Opcode::Joinnum => joinnum(i),
+        Opcode::Contraction => contraction(i),
+        Opcode::Exactdots => exactdots(i),
+        _ => fail(i),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enumset::enum_set;
+
+    #[test]
+    fn largesign_test() {
+        assert_eq!(
+            largesign("largesign überall 123"),
+            Ok(("", Rule::Largesign { word: "überall", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] })));
+        assert_eq!(
+            largesign("largesign அஇ 123"),
+            Ok(("", Rule::Largesign { word: "அஇ", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] })));
+    }
+
+    #[test]
+    fn joinword_test() {
+        assert_eq!(
+            joinword("joinword haha 123"),
+            Ok(("", Rule::Joinword { word: "haha", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] })));
+        assert_eq!(
+            joinword("joinword அஇ 123"),
+            Ok(("", Rule::Joinword { word: "அஇ", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] })));
+    }
+
+    #[test]
+    fn grouping_test() {
+        assert_eq!(
+            grouping("grouping parentheses () 12356,23456"),
+            Ok(("", Rule::Grouping { name: "parentheses", open: '(', close: ')',
+                                     open_dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT5 | BrailleDot::DOT6],
+                                     close_dots: vec![BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5 | BrailleDot::DOT6] })));
+        assert!(grouping("grouping parentheses ( 12356,23456").is_err());
+        assert!(grouping("grouping parentheses () 12356").is_err());
+    }
+
+    #[test]
+    fn match_test() {
+        assert_eq!(
+            r#match("match %[^_~]|[$s] ab - 12"),
+            Ok(("", Rule::Match { pre: "%[^_~]|[$s]", chars: "ab", post: "-", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2],
+                                  prefixes: Prefixes::empty() })));
+        assert_eq!(
+            r#match("nofor match - ab - 12").map(|(_, rule)| rule.to_string()),
+            Ok("nofor match - ab - 12".to_string()));
+        assert!(r#match("match - ab 12").is_err());
+    }
+
+    #[test]
+    fn backmatch_test() {
+        assert_eq!(
+            backmatch("backmatch [$l] a - 1"),
+            Ok(("", Rule::Backmatch { pre: "[$l]", chars: "a", post: "-", dots: vec![enum_set!(BrailleDot::DOT1)],
+                                      prefixes: Prefixes::empty() })));
+        assert!(backmatch("backmatch [$l] a -").is_err());
+    }
+
+    #[test]
+    fn literal_test() {
+        assert_eq!(literal("literal http://"), Ok(("", Rule::Literal { chars: "http://" })));
+        assert!(literal("literal").is_err());
+    }
+
+    #[test]
+    fn always_test() {
+        assert_eq!(always("always the 2346"),
+                   Ok(("", Rule::Always { chars: "the", dots: vec![BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(always("nocross always the 2346"),
+                   Ok(("", Rule::Always { chars: "the", dots: vec![BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Nocross) })));
+    }
+
+    #[test]
+    fn word_test() {
+        assert_eq!(word("word can 14"),
+                   Ok(("", Rule::Word { chars: "can", dots: Braille::Explicit(vec![BrailleDot::DOT1 | BrailleDot::DOT4]),
+                                        prefixes: Prefixes::empty() })));
+        assert_eq!(word("nocross word ab ="),
+                   Ok(("", Rule::Word { chars: "ab", dots: Braille::Implicit, prefixes: enum_set!(Prefix::Nocross) })));
+        assert_eq!(word("word ab =").map(|(_, rule)| rule.to_string()), Ok("word ab =".to_string()));
+    }
+
+    #[test]
+    fn nocont_test() {
+        assert_eq!(nocont("nocont www"), Ok(("", Rule::Nocont { chars: "www" })));
+        assert!(nocont("nocont").is_err());
+    }
+
+    #[test]
+    fn compbrl_test() {
+        assert_eq!(compbrl("compbrl ://"), Ok(("", Rule::Compbrl { chars: "://" })));
+        assert!(compbrl("compbrl").is_err());
+    }
+
+    #[test]
+    fn comp6_test() {
+        assert_eq!(comp6("comp6 % 146"),
+                   Ok(("", Rule::Comp6 { ch: '%', dots: vec![BrailleDot::DOT1 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(comp6("noback comp6 % 146"),
+                   Ok(("", Rule::Comp6 { ch: '%', dots: vec![BrailleDot::DOT1 | BrailleDot::DOT4 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Noback) })));
+    }
+
+    #[test]
+    fn replace_test() {
+        assert_eq!(replace("replace ß ss"), Ok(("", Rule::Replace { from: "ß", to: Some("ss") })));
+        assert_eq!(replace("replace \u{ad}"), Ok(("", Rule::Replace { from: "\u{ad}", to: None })));
+        assert_eq!(replace("replace ß "), Ok((" ", Rule::Replace { from: "ß", to: None })));
+        assert_eq!(replace("replace ß ss").map(|(_, rule)| rule.to_string()), Ok("replace ß ss".to_string()));
+    }
+
+    #[test]
+    fn repeated_test() {
+        assert_eq!(repeated("repeated --- 36-36-36"),
+                   Ok(("", Rule::Repeated { chars: "---", dots: vec![BrailleDot::DOT3 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(repeated("nofor repeated --- 36-36-36"),
+                   Ok(("", Rule::Repeated { chars: "---", dots: vec![BrailleDot::DOT3 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT6, BrailleDot::DOT3 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Nofor) })));
+    }
+
+    #[test]
+    fn repword_test() {
+        assert_eq!(repword("repword - 123456"),
+                   Ok(("", Rule::Repword { chars: "-", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5 | BrailleDot::DOT6], prefixes: Prefixes::empty() })));
+        assert_eq!(repword("nofor repword - 123456"),
+                   Ok(("", Rule::Repword { chars: "-", dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5 | BrailleDot::DOT6], prefixes: enum_set!(Prefix::Nofor) })));
+    }
+
+    #[test]
+    fn rependword_test() {
+        assert_eq!(rependword("rependword - 123456,36"),
+                   Ok(("", Rule::Rependword { chars: "-",
+                                              dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT5 | BrailleDot::DOT6],
+                                              otherdots: vec![BrailleDot::DOT3 | BrailleDot::DOT6],
+                                              prefixes: Prefixes::empty() })));
+        assert!(rependword("rependword - 123456").is_err());
+    }
+
+    /// The opcodes that translate characters at a position in a word
+    /// or number, all with the same operands.
+    const POSITION_OPCODES: &[Opcode] = &[
+        Opcode::Begword, Opcode::Midword, Opcode::Endword, Opcode::Partword, Opcode::Begmidword,
+        Opcode::Midendword, Opcode::Prfxword, Opcode::Sufword, Opcode::Lowword, Opcode::Prepunc,
+        Opcode::Postpunc, Opcode::Begnum, Opcode::Midnum, Opcode::Endnum, Opcode::Joinnum,
+    ];
+
+    #[test]
+    fn position_opcodes_test() {
+        for opcode in POSITION_OPCODES {
+            for source in [format!("{} ab 1-12", opcode), format!("nofor {} ab 1-12", opcode)] {
+                let (rest, parsed) = rule(*opcode, &source).unwrap();
+                assert_eq!((rest, parsed.opcode(), parsed.to_string()), ("", *opcode, source.clone()));
+            }
+        }
+        assert_eq!(sufword("nofor sufword less 46-234"),
+                   Ok(("", Rule::Sufword { chars: "less", dots: vec![BrailleDot::DOT4 | BrailleDot::DOT6, BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4], prefixes: enum_set!(Prefix::Nofor) })));
+        assert_eq!(endword("endword ing 346"),
+                   Ok(("", Rule::Endword { chars: "ing", dots: Braille::Explicit(vec![BrailleDot::DOT3 | BrailleDot::DOT4 | BrailleDot::DOT6]), prefixes: Prefixes::empty() })));
+    }
+
+    #[test]
+    fn position_opcodes_invalid_test() {
+        for opcode in POSITION_OPCODES {
+            // characters or dots missing
+            assert!(rule(*opcode, opcode.keyword()).is_err());
+            assert!(rule(*opcode, &format!("{} ab", opcode)).is_err());
+            assert!(rule(*opcode, &format!("{} ab ", opcode)).is_err());
+            // dots that are not dot numbers
+            assert!(rule(*opcode, &format!("{} ab xy", opcode)).is_err());
+            assert_ne!(rule(*opcode, &format!("{} ab 1-2z", opcode)).map(|(rest, _)| rest), Ok(""));
+        }
+    }
+
+    #[test]
+    fn implicit_dots_test() {
+        assert_eq!(endword("endword ing ="), Ok(("", Rule::Endword { chars: "ing", dots: Braille::Implicit, prefixes: Prefixes::empty() })));
+        assert_eq!(endnum("endnum st ="), Ok(("", Rule::Endnum { chars: "st", dots: Braille::Implicit, prefixes: Prefixes::empty() })));
+        assert!(begword("begword ab =").is_err());
+    }
+
+    #[test]
+    fn contraction_test() {
+        assert_eq!(contraction("contraction ab"), Ok(("", Rule::Contraction { chars: "ab" })));
+        assert!(contraction("contraction").is_err());
+    }
+
+    #[test]
+    fn exactdots_test() {
+        assert_eq!(exactdots("exactdots @4-1"),
+                   Ok(("", Rule::Exactdots { dots: vec![enum_set!(BrailleDot::DOT4), enum_set!(BrailleDot::DOT1)] })));
+        assert_eq!(exactdots("exactdots @4-1").map(|(_, rule)| rule.to_string()), Ok("exactdots @4-1".to_string()));
+        assert!(exactdots("exactdots 4-1").is_err());
+    }
+}
@@ -0,0 +1,449 @@
+//! Operands shared by many opcodes.
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::bytes::complete::is_a;
+use nom::bytes::complete::is_not;
+use nom::bytes::complete::take_while1;
+use nom::bytes::complete::take_while_m_n;
+use nom::character::complete::alpha1;
+use nom::character::complete::hex_digit1;
+use nom::character::complete::digit1;
+use nom::character::complete::none_of;
+use nom::combinator::map;
+use nom::combinator::map_opt;
+use nom::combinator::map_res;
+use nom::combinator::value;
+use nom::multi::separated_list1;
+use nom::sequence::preceded;
+use nom::sequence::separated_pair;
+
+use enumset::EnumSet;
+use enumset::EnumSetType;
+use enumset::enum_set;
+
+use nom::IResult;
+use std::fmt;
+//use nom_unicode::complete::alpha1 as unicode_alpha1;
+
+#[derive(PartialEq, Debug)]
+pub enum Position {
+    Before,
+    After,
+}
+
+#[derive(EnumSetType, Debug)]
+pub enum BrailleDot {
+    DOT0,
+    DOT1,
+    DOT2,
+    DOT3,
+    DOT4,
+    DOT5,
+    DOT6,
+    DOT7,
+    DOT8,
+    DOT9,
+    DOTA,
+    DOTB,
+    DOTC,
+    DOTD,
+    DOTE,
+    DOTF,
+}
+
+pub type BrailleChar = EnumSet<BrailleDot>;
+pub type BrailleChars = Vec<BrailleChar>;
+
+/// The dots operand of a translation rule, which can be `=` to use
+/// the dots of the character definitions instead.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Braille {
+    Explicit(BrailleChars),
+    Implicit,
+}
+
+fn char_to_dot(char: char) -> Option<BrailleDot> {
+    match char {
+        '0' => Some(BrailleDot::DOT0),
+        '1' => Some(BrailleDot::DOT1),
+        '2' => Some(BrailleDot::DOT2),
+        '3' => Some(BrailleDot::DOT3),
+        '4' => Some(BrailleDot::DOT4),
+        '5' => Some(BrailleDot::DOT5),
+        '6' => Some(BrailleDot::DOT6),
+        '7' => Some(BrailleDot::DOT7),
+        '8' => Some(BrailleDot::DOT8),
+        '9' => Some(BrailleDot::DOT9),
+        'a' | 'A' => Some(BrailleDot::DOTA),
+        'b' | 'B' => Some(BrailleDot::DOTB),
+        'c' | 'C' => Some(BrailleDot::DOTC),
+        'd' | 'D' => Some(BrailleDot::DOTD),
+        'e' | 'E' => Some(BrailleDot::DOTE),
+        'f' | 'F' => Some(BrailleDot::DOTF),
+        _ => None,
+    }
+}
+
+/// Converts dot numbers such as `"123"` to a braille cell. Panics on
+/// characters that are not dot numbers, see the `dots!` macro.
+pub fn chars_to_dots(chars: &str) -> BrailleChar {
+    chars.chars().map(|c| char_to_dot(c).unwrap()).collect()
+}
+
+fn dot_to_char(dot: BrailleDot) -> char {
+    match dot {
+        BrailleDot::DOT0 => '0',
+        BrailleDot::DOT1 => '1',
+        BrailleDot::DOT2 => '2',
+        BrailleDot::DOT3 => '3',
+        BrailleDot::DOT4 => '4',
+        BrailleDot::DOT5 => '5',
+        BrailleDot::DOT6 => '6',
+        BrailleDot::DOT7 => '7',
+        BrailleDot::DOT8 => '8',
+        BrailleDot::DOT9 => '9',
+        BrailleDot::DOTA => 'a',
+        BrailleDot::DOTB => 'b',
+        BrailleDot::DOTC => 'c',
+        BrailleDot::DOTD => 'd',
+        BrailleDot::DOTE => 'e',
+        BrailleDot::DOTF => 'f',
+    }
+}
+
+/// Renders dots in canonical form, i.e. lowercase and with the dots
+/// of each cell in ascending order. `21-3` and `12-3` both render as
+/// `12-3`.
+pub fn dots_to_string(dots: &BrailleChars) -> String {
+    dots.iter()
+        .map(|cell| cell.iter().map(dot_to_char).collect::<String>())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+fn dot_to_unicode_bit(dot: BrailleDot) -> Option<u32> {
+    match dot {
+        BrailleDot::DOT0 => Some(0),
+        BrailleDot::DOT1 => Some(0x01),
+        BrailleDot::DOT2 => Some(0x02),
+        BrailleDot::DOT3 => Some(0x04),
+        BrailleDot::DOT4 => Some(0x08),
+        BrailleDot::DOT5 => Some(0x10),
+        BrailleDot::DOT6 => Some(0x20),
+        BrailleDot::DOT7 => Some(0x40),
+        BrailleDot::DOT8 => Some(0x80),
+        _ => None,
+    }
+}
+
+/// Renders dots as Unicode braille patterns. Returns `None` if the
+/// dots use virtual dots (9 to f) which have no Unicode equivalent.
+pub fn dots_to_unicode(dots: &BrailleChars) -> Option<String> {
+    dots.iter()
+        .map(|cell| {
+            cell.iter()
+                .map(dot_to_unicode_bit)
+                .sum::<Option<u32>>()
+                .and_then(|bits| char::from_u32(0x2800 + bits))
+        })
+        .collect()
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Position::Before => write!(f, "before"),
+            Position::After => write!(f, "after"),
+        }
+    }
+}
+
+pub fn chars(input: &str) -> IResult<&str, &str> {
+    is_not(" \t\r\n")(input)
+    //unicode_alpha1(input)
+}
+
+fn hex_char<'a>(prefix: &'static str, digits: usize) -> impl FnMut(&'a str) -> IResult<&'a str, char> {
+    map_opt(preceded(tag(prefix), take_while_m_n(digits, digits, |c: char| c.is_ascii_hexdigit())),
+            |hex| u32::from_str_radix(hex, 16).ok().and_then(char::from_u32))
+}
+
+/// An escape sequence such as `\s` for a space or `\x00e4` for `ä`.
+fn escaped_char(input: &str) -> IResult<&str, char> {
+    preceded(tag("\\"), alt((
+        value(' ', tag("s")),
+        value('\t', tag("t")),
+        value('\n', tag("n")),
+        value('\r', tag("r")),
+        value('\x1b', tag("e")),
+        value('\x0c', tag("f")),
+        value('\x0b', tag("v")),
+        value('\\', tag("\\")),
+        hex_char("x", 4),
+        hex_char("y", 5),
+        hex_char("z", 8),
+    )))(input)
+}
+
+pub fn single_char(input: &str) -> IResult<&str, char> {
+    alt((escaped_char, none_of(" \t\r\n")))(input)
+}
+
+/// The digits zero of the decimal digits of Unicode 15.1, i.e. the
+/// characters of the general category Nd, which always come in runs
+/// of ten from zero to nine.
+const DECIMAL_ZEROS: [u32; 68] = [
+    0x0030, 0x0660, 0x06f0, 0x07c0, 0x0966, 0x09e6, 0x0a66, 0x0ae6, 0x0b66, 0x0be6,
+    0x0c66, 0x0ce6, 0x0d66, 0x0de6, 0x0e50, 0x0ed0, 0x0f20, 0x1040, 0x1090, 0x17e0,
+    0x1810, 0x1946, 0x19d0, 0x1a80, 0x1a90, 0x1b50, 0x1bb0, 0x1c40, 0x1c50, 0xa620,
+    0xa8d0, 0xa900, 0xa9d0, 0xa9f0, 0xaa50, 0xabf0, 0xff10, 0x104a0, 0x10d30, 0x11066,
+    0x110f0, 0x11136, 0x111d0, 0x112f0, 0x11450, 0x114d0, 0x11650, 0x116c0, 0x11730, 0x118e0,
+    0x11950, 0x11c50, 0x11d50, 0x11da0, 0x11f50, 0x16a60, 0x16ac0, 0x16b50, 0x1d7ce, 0x1d7d8,
+    0x1d7e2, 0x1d7ec, 0x1d7f6, 0x1e140, 0x1e2f0, 0x1e4f0, 0x1e950, 0x1fbf0,
+];
+
+/// Whether `c` is a decimal digit of any script, such as `7` or `۲`.
+/// Other numeric characters such as `½` or `Ⅳ` are not.
+pub fn is_decimal_digit(c: char) -> bool {
+    let zeros = DECIMAL_ZEROS.partition_point(|zero| *zero <= c as u32);
+    zeros > 0 && c as u32 - DECIMAL_ZEROS[zeros - 1] < 10
+}
+
+/// Prints a character operand, escaping characters that cannot appear
+/// literally in a table.
+pub fn char_to_string(c: char) -> String {
+    match c {
+        ' ' => "\\s".to_string(),
+        '\t' => "\\t".to_string(),
+        '\n' => "\\n".to_string(),
+        '\r' => "\\r".to_string(),
+        '\x1b' => "\\e".to_string(),
+        '\x0c' => "\\f".to_string(),
+        '\x0b' => "\\v".to_string(),
+        '\\' => "\\\\".to_string(),
+        c if c.is_control() || c.is_whitespace() => format!("\\x{:04x}", c as u32),
+        c => c.to_string(),
+    }
+}
+
+pub fn ascii_chars(input: &str) -> IResult<&str, &str> {
+    alpha1(input)
+}
+
+/// The name of e.g. a class or a grouping, which consists of letters
+/// only.
+pub fn name(input: &str) -> IResult<&str, &str> {
+    alpha1(input)
+}
+
+/// Two dot patterns separated by a comma, e.g. `12356,23456`.
+pub fn dots_pair(input: &str) -> IResult<&str, (BrailleChars, BrailleChars)> {
+    separated_pair(dots, tag(","), dots)(input)
+}
+
+/// Dot patterns separated by commas, e.g. `2,23,25`.
+pub fn dots_list(input: &str) -> IResult<&str, Vec<BrailleChars>> {
+    separated_list1(tag(","), dots)(input)
+}
+
+pub fn dots_list_to_string(list: &[BrailleChars]) -> String {
+    list.iter().map(dots_to_string).collect::<Vec<String>>().join(",")
+}
+
+/// The test or the action of a multipass rule. These have a syntax
+/// of their own, which is not parsed yet.
+pub fn multipass_operand(input: &str) -> IResult<&str, &str> {
+    is_not(" \t\r\n")(input)
+}
+
+/// A pattern of a `match` rule, such as `[aeiou]` or `-` for any
+/// context. Patterns have a syntax of their own, which is not parsed
+/// yet.
+pub fn pattern(input: &str) -> IResult<&str, &str> {
+    is_not(" \t\r\n")(input)
+}
+
+pub fn filename(input: &str) -> IResult<&str, &str> {
+    is_a("abcdefghijklmnopqrstuvwxyz0123456789_-.")(input)
+}
+
+fn unicode_to_dots(c: char) -> BrailleChar {
+    let bits = c as u32 - 0x2800;
+    if bits == 0 {
+        return enum_set!(BrailleDot::DOT0);
+    }
+    [BrailleDot::DOT1, BrailleDot::DOT2, BrailleDot::DOT3, BrailleDot::DOT4,
+     BrailleDot::DOT5, BrailleDot::DOT6, BrailleDot::DOT7, BrailleDot::DOT8]
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| bits & (1 << i) != 0)
+        .map(|(_, dot)| *dot)
+        .collect()
+}
+
+fn is_unicode_braille(c: char) -> bool {
+    ('\u{2800}'..='\u{28ff}').contains(&c)
+}
+
+pub fn unicode_dots(i: &str) -> IResult<&str, BrailleChars> {
+    let (input, cells) = take_while1(is_unicode_braille)(i)?;
+    Ok((input, cells.chars().map(unicode_to_dots).collect()))
+}
+
+fn hex_dots(i: &str) -> IResult<&str, BrailleChars> {
+    let (input, dots) = separated_list1(tag("-"), hex_digit1)(i)?;
+    let braille_chars: Vec<BrailleChar> = dots
+	.iter()
+	.map(|chars| chars_to_dots(chars))
+	.collect();
+    Ok((input, braille_chars))
+}
+
+pub fn dots(i: &str) -> IResult<&str, BrailleChars> {
+    alt((unicode_dots, hex_dots))(i)
+}
+
+/// Parses dots or `=`, which stands for the dots the characters are defined with.
+pub fn braille(i: &str) -> IResult<&str, Braille> {
+    alt((value(Braille::Implicit, tag("=")), map(dots, Braille::Explicit)))(i)
+}
+
+pub fn braille_to_string(braille: &Braille) -> String {
+    match braille {
+        Braille::Explicit(dots) => dots_to_string(dots),
+        Braille::Implicit => "=".to_string(),
+    }
+}
+
+/// Canonicalizes a dots operand, see `dots_to_string`.
+pub fn canonical_dots(input: &str) -> Option<String> {
+    match dots(input) {
+        Ok(("", dots)) => Some(dots_to_string(&dots)),
+        _ => None,
+    }
+}
+
+pub fn number(input: &str) -> IResult<&str, u8> {
+    map_res(digit1, |s: &str| s.parse::<u8>())(input)
+}
+
+pub fn before_or_after(input: &str) -> IResult<&str, Position> {
+    alt((
+	map(tag("before"), |_| Position::Before),
+	map(tag("after"), |_| Position::After)))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "chardef")]
+    use crate::parser::display;
+    use nom::error::Error;
+    use nom::error::ErrorKind;
+    use nom::Err;
+
+    #[test]
+    fn char_to_dot_test() {
+        assert_eq!(char_to_dot('8'), Some(BrailleDot::DOT8));
+        assert_eq!(char_to_dot('F'), Some(BrailleDot::DOTF));
+        assert_eq!(char_to_dot('z'), None);
+    }
+
+    #[test]
+    fn character_test() {
+        assert_eq!(ascii_chars("hallo"), Ok(("", "hallo")));
+        assert_eq!(ascii_chars("haLlo"), Ok(("", "haLlo")));
+    }
+
+    #[test]
+    fn is_decimal_digit_test() {
+        assert!("0123456789۲٠߉९௯๙᠐０𝟗🯹".chars().all(is_decimal_digit));
+        assert!(!"a½²Ⅳ①/:".chars().any(is_decimal_digit));
+        let zeros = DECIMAL_ZEROS.iter().map(|zero| char::from_u32(*zero).unwrap());
+        assert!(zeros.flat_map(|zero| (0..10).map(move |i| char::from_u32(zero as u32 + i).unwrap()))
+                .all(|c| c.is_numeric()));
+    }
+
+    #[test]
+    fn single_char_test() {
+        assert_eq!(single_char("a 1"), Ok((" 1", 'a')));
+        assert_eq!(single_char("\\s 0"), Ok((" 0", ' ')));
+        assert_eq!(single_char("\\t"), Ok(("", '\t')));
+        assert_eq!(single_char("\\\\"), Ok(("", '\\')));
+        assert_eq!(single_char("\\x00e4"), Ok(("", 'ä')));
+        assert_eq!(single_char("\\y1d400"), Ok(("", '𝐀')));
+        // not an escape sequence, so just a backslash
+        assert_eq!(single_char("\\q"), Ok(("q", '\\')));
+    }
+
+    #[test]
+    fn char_to_string_test() {
+        assert_eq!(char_to_string(' '), "\\s");
+        assert_eq!(char_to_string('\\'), "\\\\");
+        assert_eq!(char_to_string('\u{a0}'), "\\x00a0");
+        assert_eq!(char_to_string('ä'), "ä");
+    }
+
+    #[test]
+    fn dots_pair_test() {
+        assert_eq!(dots_pair("1,2-3"), Ok(("", (vec![enum_set!(BrailleDot::DOT1)],
+                                               vec![enum_set!(BrailleDot::DOT2), enum_set!(BrailleDot::DOT3)]))));
+        assert!(dots_pair("1").is_err());
+    }
+
+    #[test]
+    fn braille_test() {
+        assert_eq!(braille("="), Ok(("", Braille::Implicit)));
+        assert_eq!(braille("12"), Ok(("", Braille::Explicit(vec![BrailleDot::DOT1 | BrailleDot::DOT2]))));
+        assert_eq!(braille_to_string(&Braille::Implicit), "=");
+    }
+
+    #[test]
+    fn dots_test() {
+        assert_eq!(dots("123"), Ok(("",  vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] )));
+        assert_eq!(dots("1f"), Ok(("", vec![BrailleDot::DOT1 | BrailleDot::DOTF])));
+        assert_eq!(dots("123-1f"), Ok(("", vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3,
+						BrailleDot::DOT1 | BrailleDot::DOTF])));
+        assert_eq!(dots("123-1f-78"),
+		   Ok(("", vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3,
+				BrailleDot::DOT1 | BrailleDot::DOTF,
+				BrailleDot::DOT7 | BrailleDot::DOT8,
+		   ])));
+        assert_eq!(dots("21-3"), dots("12-3"));
+        assert_eq!(dots("1F-E"), dots("1f-e"));
+        assert_eq!(dots("huhu"),
+		   Err(Err::Error(Error::new("huhu", ErrorKind::HexDigit)))
+        );
+    }
+
+    #[test]
+    fn canonical_dots_test() {
+        assert_eq!(canonical_dots("21-3"), Some("12-3".to_string()));
+        assert_eq!(canonical_dots("F1-87"), Some("1f-78".to_string()));
+        assert_eq!(canonical_dots("12-3 "), None);
+        assert_eq!(canonical_dots("xyz"), None);
+    }
+
+    #[test]
+    fn unicode_dots_test() {
+        assert_eq!(dots("⠁⠃"), Ok(("", vec![enum_set!(BrailleDot::DOT1),
+                                              BrailleDot::DOT1 | BrailleDot::DOT2])));
+        assert_eq!(dots("⠀⣿ 1"), Ok((" 1", vec![enum_set!(BrailleDot::DOT0),
+                                                 BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT4 |
+                                                 BrailleDot::DOT5 | BrailleDot::DOT6 | BrailleDot::DOT7 | BrailleDot::DOT8])));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn unicode_dots_operand_test() {
+        assert_eq!(display("display a ⠁"), display("display a 1"));
+    }
+
+    #[test]
+    fn dots_to_unicode_test() {
+        assert_eq!(dots_to_unicode(&dots("1-12-0").unwrap().1), Some("⠁⠃⠀".to_string()));
+        assert_eq!(dots_to_unicode(&dots("12345678").unwrap().1), Some("⣿".to_string()));
+        assert_eq!(dots_to_unicode(&dots("1-19").unwrap().1), None);
+    }
+}
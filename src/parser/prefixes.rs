@@ -0,0 +1,70 @@
+//! The `noback`, `nofor` and `nocross` prefixes of rules.
+
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::success;
+use nom::sequence::tuple;
+use nom::error::Error;
+
+use enumset::EnumSet;
+use enumset::EnumSetType;
+use enumset::enum_set;
+
+use nom::IResult;
+
+#[derive(EnumSetType, Debug)]
+pub enum Prefix {
+    Noback,
+    Nofor,
+    Nocross,
+}
+
+pub type Prefixes = EnumSet<Prefix>;
+
+impl Prefix {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            Prefix::Noback => "noback",
+            Prefix::Nofor => "nofor",
+            Prefix::Nocross => "nocross",
+        }
+    }
+}
+
+pub(crate) fn prefixes_to_string(prefixes: &Prefixes) -> String {
+    prefixes
+        .iter()
+        .map(|prefix| format!("{} ", prefix.keyword()))
+        .collect()
+}
+
+pub fn prefixes(i: &str) -> IResult<&str, Prefixes> {
+    alt((
+	map(tuple((tag("noback"), space1, tag("nocross"), space1)), |_| Prefix::Noback | Prefix::Nocross),
+	map(tuple((tag("nofor"), space1, tag("nocross"), space1)), |_| Prefix::Nofor | Prefix::Nocross),
+	map(tuple((tag("nofor"), space1)), |_| enum_set!(Prefix::Nofor)),
+	map(tuple((tag("noback"), space1)), |_| enum_set!(Prefix::Noback)),
+	map(tuple((tag("nocross"), space1)), |_| enum_set!(Prefix::Nocross)),
+	success::<_,_,Error<_>>(Prefixes::empty()),
+    ))(i)
+}
+
+#[cfg(all(test, feature = "chardef"))]
+mod tests {
+    use super::*;
+    use crate::parser::{display, BrailleDot, Rule};
+
+    #[test]
+    fn prefixes_test() {
+        assert_eq!(display("nocross display haha 122"),
+		   Ok(("", Rule::Display { chars: "haha",
+					   dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2],
+					   prefixes: enum_set!(Prefix::Nocross) })));
+        assert_eq!(display("noback nocross display haha 122"),
+		   Ok(("", Rule::Display { chars: "haha",
+					   dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2],
+					   prefixes: Prefix::Noback | Prefix::Nocross })));
+    }
+}
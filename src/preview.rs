@@ -0,0 +1,163 @@
+//! Human readable previews of rules, e.g. for editor hovers.
+
+use crate::parser::{dots_to_string, dots_to_unicode, Braille, BrailleChars, Position, Rule};
+
+fn cells(dots: &BrailleChars) -> String {
+    dots_to_unicode(dots).unwrap_or_else(|| dots_to_string(dots))
+}
+
+fn braille_cells(braille: &Braille) -> String {
+    match braille {
+        Braille::Explicit(dots) => cells(dots),
+        Braille::Implicit => "the dots of its characters".to_string(),
+    }
+}
+
+fn sentence(rule: &Rule) -> String {
+    match rule {
+        Rule::Include { filename } => format!("Includes the table {}", filename),
+        Rule::Undefined { dots } => format!("Shows undefined characters as {}", cells(dots)),
+        Rule::Display { chars, dots, .. } => format!("Shows {} as {} on a braille display", chars, cells(dots)),
+        Rule::Space { ch, dots, .. } => format!("Defines {:?} as a space with {}", ch, cells(dots)),
+        Rule::Multind { chars, dots, .. } => format!("Combines the indicators {} into {}", chars, cells(dots)),
+        Rule::Punctuation { ch, dots, .. } => format!("Defines {:?} as punctuation with {}", ch, cells(dots)),
+        Rule::Digit { ch, dots } => format!("Defines {:?} as a digit with {}", ch, cells(dots)),
+        Rule::Litdigit { chars, dots } => format!("Translates the digits {} to {} in literary context", chars, cells(dots)),
+        Rule::Letter { ch, dots, .. } => format!("Defines {:?} as a letter with {}", ch, cells(dots)),
+        Rule::Uppercase { ch, dots, .. } => format!("Defines {:?} as an uppercase letter with {}", ch, cells(dots)),
+        Rule::Lowercase { ch, dots, .. } => format!("Defines {:?} as a lowercase letter with {}", ch, cells(dots)),
+        Rule::Sign { ch, dots, .. } => format!("Defines {:?} as a sign with {}", ch, cells(dots)),
+        Rule::Math { ch, dots, .. } => format!("Defines {:?} as a mathematical symbol with {}", ch, cells(dots)),
+        Rule::Uplow { upper, lower, dots, lower_dots } => format!("Defines {:?} and {:?} as a pair of uppercase and lowercase letters with {} and {}",
+                                                                  upper, lower, cells(dots), cells(lower_dots.as_ref().unwrap_or(dots))),
+        Rule::Base { attribute, derived, base } => format!("Defines {:?} as {:?} with the attribute {}", derived, base, attribute),
+        Rule::Attribute { name, chars } => format!("Adds {} to the character class {}", chars, name),
+        Rule::Class { name, chars } => format!("Adds {} to the character class {}", chars, name),
+        Rule::Modeletter { chars, dots, .. } => format!("Marks a single letter in {} mode with {}", chars, cells(dots)),
+        Rule::Capsletter { dots, .. } => format!("Marks a single capital letter with {}", cells(dots)),
+        Rule::Begmodeword { chars, dots, .. } => format!("Marks the beginning of a word in {} mode with {}", chars, cells(dots)),
+        Rule::Begcapsword { dots, .. } => format!("Marks the beginning of a capitalized word with {}", cells(dots)),
+        Rule::Endcapsword { dots, .. } => format!("Marks the end of a capitalized word with {}", cells(dots)),
+        Rule::Emphclass { name } => format!("Defines the emphasis class {}", name),
+        Rule::Capsmodechars { chars } => format!("Keeps capitalization mode across {}", chars),
+        Rule::Begcaps { dots } => format!("Marks the beginning of a capitalized block with {}", cells(dots)),
+        Rule::Endcaps { dots } => format!("Marks the end of a capitalized block with {}", cells(dots)),
+        Rule::Begcapsphrase { dots } => format!("Marks the beginning of a capitalized phrase with {}", cells(dots)),
+        Rule::Endcapsphrase { dots, position } => match position {
+            Position::Before => format!("Marks the end of a capitalized phrase with {} before the last word", cells(dots)),
+            Position::After => format!("Marks the end of a capitalized phrase with {} after the last word", cells(dots)),
+        },
+        Rule::Lencapsphrase { length } => format!("Treats {} or more capitalized words as a phrase", length),
+        Rule::Capsnocont => "Does not contract capitalized words".to_string(),
+        Rule::Letsign { dots } => format!("Uses {} as the letter sign", cells(dots)),
+        Rule::Noletsign { chars } => format!("Does not put a letter sign before {}", chars),
+        Rule::Noletsignbefore { chars } => format!("Does not put a letter sign before a letter preceded by {}", chars),
+        Rule::Noletsignafter { chars } => format!("Does not put a letter sign before a letter followed by {}", chars),
+        Rule::Nocontractsign { dots } => format!("Uses {} to mark letters that are not a contraction", cells(dots)),
+        Rule::Numsign { dots } => format!("Uses {} as the number sign", cells(dots)),
+        Rule::Nonumsign { dots } => format!("Uses {} to end numeric mode", cells(dots)),
+        Rule::Numericmodechars { chars } => format!("Keeps numeric mode active across {}", chars),
+        Rule::Largesign { word, dots } => format!("Translates {} to {} without space to a following large sign", word, cells(dots)),
+        Rule::Syllable { word, dots } => format!("Translates the syllable {} to {}", word, cells(dots)),
+        Rule::Joinword { word, dots } => format!("Translates {} to {} and joins it to the following word", word, cells(dots)),
+        Rule::Grouping { name, open, close, open_dots, close_dots } =>
+            format!("Defines the grouping {} that opens with {} as {} and closes with {} as {}",
+                    name, open, cells(open_dots), close, cells(close_dots)),
+        Rule::Match { pre, chars, post, dots, .. } =>
+            format!("Translates {} to {} between {} and {}", chars, cells(dots), pre, post),
+        Rule::Backmatch { pre, chars, post, dots, .. } =>
+            format!("Back-translates {} to {} between {} and {}", cells(dots), chars, pre, post),
+        Rule::Literal { chars } => format!("Passes {} through untranslated", chars),
+        Rule::Always { chars, dots, .. } => format!("Translates {} to {} wherever it occurs", chars, cells(dots)),
+        Rule::Word { chars, dots, .. } => format!("Translates the word {} to {}", chars, braille_cells(dots)),
+        Rule::Nocont { chars } => format!("Never contracts {}", chars),
+        Rule::Compbrl { chars } => format!("Shows words containing {} in computer braille", chars),
+        Rule::Comp6 { ch, dots, .. } => format!("Shows {:?} as {} in 6-dot computer braille", ch, cells(dots)),
+        Rule::Replace { from, to: None } => format!("Removes {} before translation", from),
+        Rule::Repeated { chars, dots, .. } => format!("Translates repetitions of {} to a single {}", chars, cells(dots)),
+        Rule::Repword { chars, dots, .. } => format!("Translates {} between a word and its repetition to {}", chars, cells(dots)),
+        Rule::Rependword { chars, dots, otherdots, .. } =>
+            format!("Translates {} between a word and its repetition to {}, or to {} if only the end is repeated",
+                    chars, cells(dots), cells(otherdots)),
+        Rule::Begword { chars, dots, .. } => format!("Translates {} to {} at the beginning of a word", chars, cells(dots)),
+        Rule::Midword { chars, dots, .. } => format!("Translates {} to {} in the middle of a word", chars, cells(dots)),
+        Rule::Endword { chars, dots, .. } => format!("Translates {} to {} at the end of a word", chars, braille_cells(dots)),
+        Rule::Partword { chars, dots, .. } => format!("Translates {} to {} as part of a longer word", chars, cells(dots)),
+        Rule::Begmidword { chars, dots, .. } => format!("Translates {} to {} at the beginning or in the middle of a word", chars, cells(dots)),
+        Rule::Midendword { chars, dots, .. } => format!("Translates {} to {} in the middle or at the end of a word", chars, cells(dots)),
+        Rule::Prfxword { chars, dots, .. } => format!("Translates {} to {} at the beginning of a word or as a whole word", chars, cells(dots)),
+        Rule::Sufword { chars, dots, .. } => format!("Translates {} to {} at the end of a word or as a whole word", chars, cells(dots)),
+        Rule::Lowword { chars, dots, .. } => format!("Translates the word {} to {} if it is surrounded by whitespace", chars, cells(dots)),
+        Rule::Prepunc { chars, dots, .. } => format!("Translates {} to {} as punctuation before a word", chars, cells(dots)),
+        Rule::Postpunc { chars, dots, .. } => format!("Translates {} to {} as punctuation after a word", chars, cells(dots)),
+        Rule::Begnum { chars, dots, .. } => format!("Translates {} to {} at the beginning of a number", chars, cells(dots)),
+        Rule::Midnum { chars, dots, .. } => format!("Translates {} to {} in the middle of a number", chars, cells(dots)),
+        Rule::Endnum { chars, dots, .. } => format!("Translates {} to {} at the end of a number", chars, braille_cells(dots)),
+        Rule::Joinnum { chars, dots, .. } => format!("Translates {} to {} and joins it to the following number", chars, cells(dots)),
+        Rule::Contraction { chars } => format!("Marks {} as a contraction that needs a letter sign", chars),
+        Rule::Exactdots { dots } => format!("Translates @{} in the input to exactly {}", dots_to_string(dots), cells(dots)),
+        Rule::Replace { from, to: Some(to) } => format!("Replaces {} with {} before translation", from, to),
+        Rule::Swapcd { name, chars, dots } =>
+            format!("Defines the swap class {} from {} to {}", name, chars,
+                    dots.iter().map(cells).collect::<Vec<_>>().join(", ")),
+        Rule::Swapdd { name, from, to } =>
+            format!("Defines the swap class {} from {} to {}", name,
+                    from.iter().map(cells).collect::<Vec<_>>().join(", "),
+                    to.iter().map(cells).collect::<Vec<_>>().join(", ")),
+        Rule::Swapcc { name, from, to } => format!("Defines the swap class {} from {} to {}", name, from, to),
+        Rule::Context { test, action, .. } => format!("Applies {} where the input matches {} during translation", action, test),
+        Rule::Correct { test, action, .. } => format!("Applies {} where the input matches {} before translation", action, test),
+        Rule::Pass2 { test, action, .. } => format!("Applies {} where the braille matches {} in the second pass", action, test),
+        Rule::Pass3 { test, action, .. } => format!("Applies {} where the braille matches {} in the third pass", action, test),
+        Rule::Pass4 { test, action, .. } => format!("Applies {} where the braille matches {} in the fourth pass", action, test),
+    }
+}
+
+/// A preview of a rule consisting of the rule in canonical syntax
+/// followed by a sentence describing what it does, e.g.
+///
+/// ```text
+/// joinword to 235: Translates to to ⠖ and joins it to the following word
+/// ```
+pub fn preview(rule: &Rule) -> String {
+    format!("{}: {}", rule, sentence(rule))
+}
+
+#[cfg(all(test, any(feature = "chardef", feature = "translation", feature = "emphasis")))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "chardef")]
+    use crate::parser::{display, undefined};
+    #[cfg(feature = "emphasis")]
+    use crate::parser::lencapsphrase;
+    #[cfg(feature = "translation")]
+    use crate::parser::joinword;
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn preview_test() {
+        let (_, rule) = joinword("joinword to 235").unwrap();
+        assert_eq!(preview(&rule), "joinword to 235: Translates to to ⠖ and joins it to the following word");
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn chardef_preview_test() {
+        let (_, rule) = display("nocross display a 1").unwrap();
+        assert_eq!(preview(&rule), "nocross display a 1: Shows a as ⠁ on a braille display");
+    }
+
+    #[test]
+    #[cfg(feature = "emphasis")]
+    fn emphasis_preview_test() {
+        let (_, rule) = lencapsphrase("lencapsphrase 3").unwrap();
+        assert_eq!(preview(&rule), "lencapsphrase 3: Treats 3 or more capitalized words as a phrase");
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn virtual_dots_test() {
+        let (_, rule) = undefined("undefined 12-9a").unwrap();
+        assert_eq!(preview(&rule), "undefined 12-9a: Shows undefined characters as 12-9a");
+    }
+}
@@ -0,0 +1,77 @@
+//! Rules that are duplicated across the files of a table.
+//!
+//! Tables in the liblouis repository often copy rules into several
+//! included files, which then drift apart. Given the files of a
+//! resolved include closure, `duplicates` reports every rule that
+//! occurs in more than one of them.
+
+use std::collections::HashMap;
+
+use crate::fingerprint::{fingerprint, Fingerprint};
+use crate::parser::Line;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Location<'f> {
+    pub file: &'f str,
+    /// The zero based index of the line in the file.
+    pub line: usize,
+}
+
+/// A rule and all places it occurs in.
+#[derive(PartialEq, Debug)]
+pub struct DuplicateGroup<'f> {
+    pub fingerprint: Fingerprint,
+    /// The canonical form of the rule.
+    pub rule: String,
+    pub locations: Vec<Location<'f>>,
+}
+
+/// The rules of `files` that occur in at least two different files,
+/// in the order of their first occurrence. Rules are compared in
+/// canonical form, so formatting and end comments do not matter.
+pub fn duplicates<'f>(files: &[(&'f str, &[Line])]) -> Vec<DuplicateGroup<'f>> {
+    let mut groups: Vec<DuplicateGroup<'f>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (file, lines) in files {
+        for (line, parsed) in lines.iter().enumerate() {
+            let Line::Rule { rule, .. } = parsed else { continue };
+            let canonical = rule.to_string();
+            let location = Location { file, line };
+            match index.get(&canonical) {
+                Some(group) => groups[*group].locations.push(location),
+                None => {
+                    index.insert(canonical.clone(), groups.len());
+                    groups.push(DuplicateGroup { fingerprint: fingerprint(rule), rule: canonical, locations: vec![location] });
+                }
+            }
+        }
+    }
+    groups.retain(|group| group.locations.iter().any(|location| location.file != group.locations[0].file));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::table;
+
+    #[test]
+    #[cfg(all(feature = "meta", feature = "chardef"))]
+    fn duplicates_test() {
+        let (_, chardefs) = table("punctuation . 256\ndigit 1 1\n").unwrap();
+        let (_, en) = table("include chardefs.cti\npunctuation  .  256 copied\njoinword to 235\n").unwrap();
+        let (_, de) = table("digit 1 1\ndigit 1 1\njoinword to 2356\n").unwrap();
+        let groups = duplicates(&[("chardefs.cti", &chardefs), ("en.ctb", &en), ("de.ctb", &de)]);
+        assert_eq!(groups.iter().map(|group| group.rule.as_str()).collect::<Vec<_>>(),
+                   vec!["punctuation . 256", "digit 1 1"]);
+        assert_eq!(groups[0].locations,
+                   vec![Location { file: "chardefs.cti", line: 0 }, Location { file: "en.ctb", line: 1 }]);
+        assert_eq!(groups[1].locations.len(), 3);
+    }
+
+    #[test]
+    fn same_file_duplicates_test() {
+        let (_, lines) = table("digit 1 1\ndigit 1 1\n").unwrap();
+        assert_eq!(duplicates(&[("de.ctb", &lines)]), vec![]);
+    }
+}
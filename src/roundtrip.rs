@@ -0,0 +1,104 @@
+//! Checks that printing a parsed table loses nothing.
+//!
+//! Tools that rewrite tables, e.g. a formatter or a migration, print
+//! the AST in canonical form. `verify_roundtrip` parses a table,
+//! prints it, parses the printed text again and reports every line
+//! whose meaning changed on the way.
+//!
+//! The AST does not keep the original spacing, so the printed text is
+//! in general not byte identical to the source. This is reported, but
+//! not counted as a divergence. A stricter mode that requires byte
+//! equality needs a concrete syntax tree that keeps whitespace and
+//! comments as they are, which this crate does not have yet.
+
+use crate::parser::{line, table, Line};
+
+/// A line whose printed form does not parse back to the same line.
+#[derive(PartialEq, Debug)]
+pub struct Divergence {
+    /// The zero based index of the line in the source.
+    pub line: usize,
+    /// The canonical form of the line as parsed from the source.
+    pub printed: String,
+    /// The canonical form of the line parsed from `printed`, or `None`
+    /// if `printed` does not parse at all.
+    pub reparsed: Option<String>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct RoundtripReport {
+    /// The byte offset of the first part of the source that does not
+    /// parse, if any. Only the lines before it are checked.
+    pub unparsed: Option<usize>,
+    pub divergences: Vec<Divergence>,
+    /// Whether the printed table is byte identical to the source.
+    pub byte_identical: bool,
+}
+
+impl RoundtripReport {
+    /// Whether the whole source parsed and every line survived the
+    /// round trip.
+    pub fn is_ok(&self) -> bool {
+        self.unparsed.is_none() && self.divergences.is_empty()
+    }
+}
+
+/// Prints the lines of a table, one per line.
+pub fn print(lines: &[Line]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Parses `source`, prints it, reparses the printed text and compares
+/// the result with the original AST line by line.
+pub fn verify_roundtrip(source: &str) -> RoundtripReport {
+    let (rest, original) = table(source).unwrap_or((source, Vec::new()));
+    let unparsed = (!rest.is_empty()).then(|| source.len() - rest.len());
+    let printed = print(&original);
+    let divergences = match table(&printed) {
+        Ok(("", reparsed)) if reparsed == original => Vec::new(),
+        _ => original
+            .iter()
+            .enumerate()
+            .filter_map(|(index, original)| {
+                let printed = original.to_string();
+                let text = format!("{}\n", printed);
+                match line(&text) {
+                    Ok(("", reparsed)) if reparsed == *original => None,
+                    Ok(("", reparsed)) => Some(Divergence { line: index, reparsed: Some(reparsed.to_string()), printed }),
+                    _ => Some(Divergence { line: index, reparsed: None, printed }),
+                }
+            })
+            .collect(),
+    };
+    RoundtripReport { unparsed, divergences, byte_identical: unparsed.is_none() && printed == source }
+}
+
+#[cfg(all(test, feature = "chardef"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_test() {
+        let report = verify_roundtrip("# a comment\n\nnocross display a 12 end\n");
+        assert_eq!(report, RoundtripReport { unparsed: None, divergences: vec![], byte_identical: true });
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn roundtrip_spacing_test() {
+        let report = verify_roundtrip("display  a 21\n  \r\n");
+        assert!(report.is_ok());
+        assert!(!report.byte_identical);
+    }
+
+    #[test]
+    fn roundtrip_unparsed_test() {
+        let report = verify_roundtrip("display a 1\nfoo\n");
+        assert_eq!(report.unparsed, Some(12));
+        assert!(!report.is_ok());
+        assert!(!report.byte_identical);
+        let report = verify_roundtrip("# a comment\ndisplay a 1\ndisplay b\ndisplay c 14\n");
+        assert_eq!(report.unparsed, Some(24));
+        assert_eq!(report.divergences, vec![]);
+    }
+}
@@ -0,0 +1,196 @@
+//! A lint for characters that do not belong to the language of a
+//! table, e.g. Cyrillic letters in a Tamil table.
+//!
+//! The language is taken from the `#+language:` metadata of the
+//! table. Only characters of a known script that is not used by the
+//! language are reported. Latin letters are always accepted, as most
+//! tables also define them for English text.
+
+use crate::classify::metadata;
+use crate::diagnostic::{Code, Diagnostic};
+use crate::opcode::OperandKind;
+use crate::table::Table;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+impl Script {
+    pub fn name(self) -> &'static str {
+        match self {
+            Script::Latin => "Latin",
+            Script::Greek => "Greek",
+            Script::Cyrillic => "Cyrillic",
+            Script::Hebrew => "Hebrew",
+            Script::Arabic => "Arabic",
+            Script::Devanagari => "Devanagari",
+            Script::Bengali => "Bengali",
+            Script::Tamil => "Tamil",
+            Script::Telugu => "Telugu",
+            Script::Kannada => "Kannada",
+            Script::Malayalam => "Malayalam",
+            Script::Thai => "Thai",
+            Script::Hangul => "Hangul",
+            Script::Kana => "Kana",
+            Script::Han => "Han",
+        }
+    }
+}
+
+/// The script of a letter, or `None` for digits, punctuation, symbols
+/// and scripts not listed in `Script`.
+pub fn script_of(c: char) -> Option<Script> {
+    match c {
+        // the multiplication and division signs
+        '\u{d7}' | '\u{f7}' => None,
+        'A'..='Z' | 'a'..='z' | '\u{c0}'..='\u{24f}' | '\u{1e00}'..='\u{1eff}' => Some(Script::Latin),
+        '\u{370}'..='\u{3ff}' | '\u{1f00}'..='\u{1fff}' => Some(Script::Greek),
+        '\u{400}'..='\u{52f}' => Some(Script::Cyrillic),
+        '\u{590}'..='\u{5ff}' => Some(Script::Hebrew),
+        '\u{600}'..='\u{6ff}' | '\u{750}'..='\u{77f}' => Some(Script::Arabic),
+        '\u{900}'..='\u{97f}' => Some(Script::Devanagari),
+        '\u{980}'..='\u{9ff}' => Some(Script::Bengali),
+        '\u{b80}'..='\u{bff}' => Some(Script::Tamil),
+        '\u{c00}'..='\u{c7f}' => Some(Script::Telugu),
+        '\u{c80}'..='\u{cff}' => Some(Script::Kannada),
+        '\u{d00}'..='\u{d7f}' => Some(Script::Malayalam),
+        '\u{e00}'..='\u{e7f}' => Some(Script::Thai),
+        '\u{1100}'..='\u{11ff}' | '\u{ac00}'..='\u{d7af}' => Some(Script::Hangul),
+        '\u{3040}'..='\u{30ff}' => Some(Script::Kana),
+        '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => Some(Script::Han),
+        _ => None,
+    }
+}
+
+/// The scripts used by a language, given as a language tag such as
+/// `ta` or `sr-Latn`. Only the primary subtag is looked at.
+pub fn scripts_of(language: &str) -> Option<&'static [Script]> {
+    let primary = language.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+    let scripts: &'static [Script] = match primary.as_str() {
+        "en" | "de" | "fr" | "es" | "it" | "nl" | "pt" | "sv" | "da" | "no" | "nb" | "nn" | "fi" | "is" | "pl"
+        | "cs" | "sk" | "sl" | "hr" | "hu" | "ro" | "tr" | "lt" | "lv" | "et" | "ga" | "cy" | "eu" | "ca" | "vi"
+        | "af" | "sw" | "mt" | "eo" => &[Script::Latin],
+        "ru" | "uk" | "be" | "bg" | "mk" | "kk" | "ky" | "mn" => &[Script::Cyrillic],
+        "sr" => &[Script::Cyrillic, Script::Latin],
+        "el" | "grc" => &[Script::Greek],
+        "he" | "yi" => &[Script::Hebrew],
+        "ar" | "fa" | "ur" | "ps" => &[Script::Arabic],
+        "hi" | "mr" | "ne" | "sa" => &[Script::Devanagari],
+        "bn" | "as" => &[Script::Bengali],
+        "ta" => &[Script::Tamil],
+        "te" => &[Script::Telugu],
+        "kn" => &[Script::Kannada],
+        "ml" => &[Script::Malayalam],
+        "th" => &[Script::Thai],
+        "ko" => &[Script::Hangul, Script::Han],
+        "ja" => &[Script::Kana, Script::Han],
+        "zh" => &[Script::Han],
+        _ => return None,
+    };
+    Some(scripts)
+}
+
+/// The configuration of the lint.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ScriptLint {
+    /// Characters that are never reported.
+    pub allowed_chars: Vec<char>,
+    /// Scripts that are accepted in addition to those of the language.
+    pub allowed_scripts: Vec<Script>,
+}
+
+impl ScriptLint {
+    /// Reports the characters in the character operands of `table`
+    /// that do not fit its language. Tables without a known language
+    /// are not checked.
+    pub fn check(&self, table: &Table) -> Vec<Diagnostic> {
+        let Some(language) = metadata(table.lines()).into_iter().find(|(key, _)| *key == "language").map(|(_, value)| value) else {
+            return Vec::new();
+        };
+        let Some(expected) = scripts_of(language) else {
+            return Vec::new();
+        };
+        let accepted = |script: Script| {
+            script == Script::Latin || expected.contains(&script) || self.allowed_scripts.contains(&script)
+        };
+        let mut diagnostics = Vec::new();
+        for line in 0..table.lines().len() {
+            for operand in table.operands(line) {
+                if !matches!(operand.kind, OperandKind::Char | OperandKind::Chars) {
+                    continue;
+                }
+                for (offset, c) in table.source()[operand.span.clone()].char_indices() {
+                    match script_of(c) {
+                        Some(script) if !accepted(script) && !self.allowed_chars.contains(&c) => {
+                            let start = operand.span.start + offset;
+                            diagnostics.push(Diagnostic {
+                                code: Code::UnexpectedScript,
+                                span: start..start + c.len_utf8(),
+                                args: vec![("char", c.to_string()),
+                                           ("script", script.name().to_string()),
+                                           ("language", language.to_string())],
+                            });
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = concat!("#+language: ta\n",
+                                 "punctuation . 256\n",
+                                 "largesign அஇ 123\n",
+                                 "largesign aж 123\n");
+
+    #[test]
+    fn script_of_test() {
+        assert_eq!(script_of('அ'), Some(Script::Tamil));
+        assert_eq!(script_of('ж'), Some(Script::Cyrillic));
+        assert_eq!(script_of('é'), Some(Script::Latin));
+        assert_eq!(script_of('.'), None);
+        assert_eq!(script_of('×'), None);
+        assert_eq!(scripts_of("sr-Latn"), Some(&[Script::Cyrillic, Script::Latin][..]));
+        assert_eq!(scripts_of("tlh"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn check_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        let diagnostics = ScriptLint::default().check(&table);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, 65..67);
+        assert_eq!(diagnostics[0].message(), "character `ж` is Cyrillic, which is not a script of language `ta`");
+    }
+
+    #[test]
+    fn allowlist_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        assert_eq!(ScriptLint { allowed_chars: vec!['ж'], ..Default::default() }.check(&table), vec![]);
+        assert_eq!(ScriptLint { allowed_scripts: vec![Script::Cyrillic], ..Default::default() }.check(&table), vec![]);
+        let (_, table) = Table::parse("largesign aж 123\n").unwrap();
+        assert_eq!(ScriptLint::default().check(&table), vec![]);
+    }
+}
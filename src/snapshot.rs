@@ -0,0 +1,127 @@
+//! Helpers for golden-file tests of parsed tables, enabled with the
+//! `test-util` feature.
+//!
+//! The snapshot forms only depend on the canonical form of the rules,
+//! not on the `Debug` output of the AST, so they stay stable as long
+//! as the tables mean the same thing.
+
+use std::fs;
+use std::path::Path;
+
+use crate::parser::Line;
+
+/// A line oriented text snapshot of a parsed table.
+pub fn snapshot(lines: &[Line]) -> String {
+    lines
+        .iter()
+        .map(|line| match line {
+            Line::Empty => "empty\n".to_string(),
+            Line::Comment { comment } => format!("comment: {}\n", comment),
+            Line::Rule { rule, comment: "" } => format!("rule: {}\n", rule),
+            Line::Rule { rule, comment } => format!("rule: {} # {}\n", rule, comment),
+            Line::Invalid { text } => format!("invalid: {}\n", text),
+        })
+        .collect()
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// A JSON snapshot of a parsed table, with one array element per line.
+pub fn snapshot_json(lines: &[Line]) -> String {
+    let elements: Vec<String> = lines
+        .iter()
+        .map(|line| match line {
+            Line::Empty => "  {\"type\": \"empty\"}".to_string(),
+            Line::Comment { comment } => format!("  {{\"type\": \"comment\", \"comment\": {}}}", json_string(comment)),
+            Line::Rule { rule, comment } => format!(
+                "  {{\"type\": \"rule\", \"opcode\": {}, \"rule\": {}, \"comment\": {}}}",
+                json_string(rule.opcode().keyword()),
+                json_string(&rule.to_string()),
+                json_string(comment)
+            ),
+            Line::Invalid { text } => format!("  {{\"type\": \"invalid\", \"text\": {}}}", json_string(text)),
+        })
+        .collect();
+    if elements.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", elements.join(",\n"))
+    }
+}
+
+/// Compares `actual` with the contents of the golden file at `path`.
+///
+/// If the file does not exist or the environment variable
+/// `UPDATE_SNAPSHOTS` is set, the file is (re)written instead.
+pub fn assert_snapshot<P: AsRef<Path>>(actual: &str, path: P) {
+    let path = path.as_ref();
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() || !path.exists() {
+        fs::write(path, actual).unwrap_or_else(|e| panic!("cannot write snapshot {}: {}", path.display(), e));
+        return;
+    }
+    let expected = fs::read_to_string(path).unwrap_or_else(|e| panic!("cannot read snapshot {}: {}", path.display(), e));
+    if expected != actual {
+        panic!("snapshot {} does not match\n--- expected\n{}\n+++ actual\n{}", path.display(), expected, actual);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "chardef")]
+    use crate::parser::table;
+
+    #[cfg(feature = "chardef")]
+    const SOURCE: &str = concat!("# a \"comment\"\n",
+                                 "\n",
+                                 "nocross display  a 21 end\n");
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn snapshot_test() {
+        let (_, lines) = table(SOURCE).unwrap();
+        assert_eq!(snapshot(&lines),
+                   concat!("comment:  a \"comment\"\n",
+                           "empty\n",
+                           "rule: nocross display a 12 # end\n"));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn snapshot_json_test() {
+        let (_, lines) = table(SOURCE).unwrap();
+        assert_eq!(snapshot_json(&lines),
+                   concat!("[\n",
+                           "  {\"type\": \"comment\", \"comment\": \" a \\\"comment\\\"\"},\n",
+                           "  {\"type\": \"empty\"},\n",
+                           "  {\"type\": \"rule\", \"opcode\": \"display\", \"rule\": \"nocross display a 12\", \"comment\": \"end\"}\n",
+                           "]\n"));
+        assert_eq!(snapshot_json(&[]), "[]\n");
+    }
+
+    #[test]
+    fn assert_snapshot_test() {
+        let path = std::env::temp_dir().join(format!("liblouis-nom-snapshot-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_snapshot("empty\n", &path);
+        assert_snapshot("empty\n", &path);
+        let result = std::panic::catch_unwind(|| assert_snapshot("comment: foo\n", &path));
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,339 @@
+//! A parsed table that remembers where each line came from, so that
+//! positions in the source can be mapped back to the AST.
+//!
+//! A `Table` is `Send` and `Sync`, so a server can parse a table once
+//! and query it from many threads, e.g. through an `Arc<Table>`. As a
+//! `Table` borrows its source, a server that keeps tables around
+//! rather shares a `CompiledTable`, which owns its source, through a
+//! `SharedTable`.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use nom::IResult;
+
+use crate::classify::{classify, Classification};
+use crate::diagnostic::{diagnostics, Diagnostic};
+use crate::display_map::{display_map, DisplayMap};
+use crate::lexer::{tokenize_line, TokenKind};
+use crate::line_index::LineIndex;
+use crate::opcode::OperandKind;
+use crate::parser::{last_line, line, Line, Rule};
+
+#[derive(PartialEq, Debug)]
+pub struct Table<'a> {
+    source: &'a str,
+    lines: Vec<Line<'a>>,
+    spans: Vec<Range<usize>>,
+    line_index: LineIndex<'a>,
+}
+
+// Fails to compile if the AST or the table stop being thread safe,
+// e.g. because of an `Rc` or a `RefCell` in a new rule.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Rule>();
+    assert_send_sync::<Line>();
+    assert_send_sync::<LineIndex>();
+    assert_send_sync::<Table>();
+    assert_send_sync::<CompiledTable>();
+};
+
+/// The operand of a rule at a position.
+#[derive(PartialEq, Debug)]
+pub struct Operand {
+    /// The index of the operand in `Opcode::operands`.
+    pub index: usize,
+    pub kind: OperandKind,
+    pub span: Range<usize>,
+}
+
+/// The nodes enclosing a position in the source of a table.
+#[derive(PartialEq, Debug)]
+pub struct Node<'t, 'a> {
+    /// The index of the line.
+    pub line: usize,
+    /// The span of the line, without line ending.
+    pub span: Range<usize>,
+    pub rule: Option<&'t Rule<'a>>,
+    pub operand: Option<Operand>,
+}
+
+impl<'a> Table<'a> {
+    /// Parses all lines of `source`. Unlike `table` this does not stop
+    /// at a line that does not parse, e.g. because of an unknown opcode
+    /// or invalid operands. The line is kept as `Line::Invalid` and
+    /// parsing continues on the next line, so the rest of a table that
+    /// is being edited can still be queried. A last line without line
+    /// ending is parsed with `last_line`, and also kept as
+    /// `Line::Invalid` if it does not parse.
+    pub fn parse(source: &'a str) -> IResult<&'a str, Table<'a>> {
+        let mut lines = Vec::new();
+        let mut spans = Vec::new();
+        let mut input = source;
+        loop {
+            let (rest, parsed) = match line(input) {
+                Ok(result) => result,
+                Err(_) if input.is_empty() => break,
+                Err(_) => match input.find('\n') {
+                    Some(end) => (&input[end + 1..], Line::Invalid { text: input[..end].trim_end_matches('\r') }),
+                    None => last_line(input).unwrap_or(("", Line::Invalid { text: input })),
+                },
+            };
+            let start = source.len() - input.len();
+            let consumed = &input[..input.len() - rest.len()];
+            let end = start + consumed.trim_end_matches(['\r', '\n']).len();
+            lines.push(parsed);
+            spans.push(start..end);
+            input = rest;
+        }
+        Ok((input, Table { source, lines, spans, line_index: LineIndex::new(source) }))
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn lines(&self) -> &[Line<'a>] {
+        &self.lines
+    }
+
+    /// The line index of the source, see `LineIndex`.
+    pub fn line_index(&self) -> &LineIndex<'a> {
+        &self.line_index
+    }
+
+    /// The span of a line, without line ending.
+    pub fn span(&self, line: usize) -> Option<Range<usize>> {
+        self.spans.get(line).cloned()
+    }
+
+    /// The nodes enclosing the byte `offset`. An offset on a line
+    /// ending belongs to the line it ends.
+    pub fn node_at(&self, offset: usize) -> Option<Node<'_, 'a>> {
+        let index = self.spans.partition_point(|span| span.end < offset);
+        let span = self.spans.get(index)?.clone();
+        if offset < span.start {
+            return None;
+        }
+        let rule = match &self.lines[index] {
+            Line::Rule { rule, .. } => Some(rule),
+            _ => None,
+        };
+        let operand = self
+            .operands(index)
+            .into_iter()
+            .find(|operand| operand.span.start <= offset && offset < operand.span.end);
+        Some(Node { line: index, span, rule, operand })
+    }
+
+    /// The operands of the rule on a line.
+    pub fn operands(&self, line: usize) -> Vec<Operand> {
+        match (self.lines.get(line), self.spans.get(line)) {
+            (Some(Line::Rule { .. }), Some(span)) => tokenize_line(&self.source[span.clone()])
+                .into_iter()
+                .filter_map(|token| match token.kind {
+                    TokenKind::Operand(kind) => Some((kind, token.span)),
+                    _ => None,
+                })
+                .enumerate()
+                .map(|(index, (kind, word))| Operand {
+                    index,
+                    kind,
+                    span: span.start + word.start..span.start + word.end,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The nodes enclosing the zero based `line` and byte `column`.
+    pub fn node_at_position(&self, line: usize, column: usize) -> Option<Node<'_, 'a>> {
+        let span = self.spans.get(line)?;
+        if span.start + column > span.end {
+            return None;
+        }
+        self.node_at(span.start + column)
+    }
+}
+
+/// A table that owns its source, together with what is compiled from
+/// it once: the display map, the classification and the diagnostics.
+#[derive(PartialEq, Debug)]
+pub struct CompiledTable {
+    source: String,
+    display_map: DisplayMap,
+    classification: Classification,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A compiled table that is cheap to clone and can be queried from
+/// many threads.
+pub type SharedTable = Arc<CompiledTable>;
+
+impl CompiledTable {
+    pub fn compile(source: String) -> CompiledTable {
+        let (_, table) = Table::parse(&source).expect("invalid lines are kept as Line::Invalid");
+        let display_map = display_map(table.lines());
+        let classification = classify(table.lines());
+        let diagnostics = diagnostics(&source);
+        CompiledTable { display_map, classification, diagnostics, source }
+    }
+
+    /// Compiles `source` into a `SharedTable`.
+    pub fn shared(source: String) -> SharedTable {
+        Arc::new(CompiledTable::compile(source))
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Parses the source again, see `Table::parse`.
+    pub fn table(&self) -> IResult<&str, Table<'_>> {
+        Table::parse(&self.source)
+    }
+
+    pub fn display_map(&self) -> &DisplayMap {
+        &self.display_map
+    }
+
+    pub fn classification(&self) -> Classification {
+        self.classification
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line_index::LineColumn;
+    #[cfg(feature = "chardef")]
+    use crate::parser::BrailleDot;
+    use std::thread;
+
+    const SOURCE: &str = concat!("# a comment\n",
+                                 "\n",
+                                 "nocross display  haha 12 end comment\r\n",
+                                 "joinword haha 123\n");
+
+    #[test]
+    fn parse_test() {
+        let (rest, table) = Table::parse(SOURCE).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(table.lines().len(), 4);
+        assert_eq!(table.span(0), Some(0..11));
+        assert_eq!(table.span(1), Some(12..12));
+        assert_eq!(table.span(2), Some(13..49));
+        assert_eq!(table.span(3), Some(51..68));
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn node_at_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        let node = table.node_at(3).unwrap();
+        assert_eq!((node.line, node.rule, node.operand), (0, None, None));
+        let node = table.node_at(30).unwrap();
+        assert_eq!(node.line, 2);
+        assert_eq!(node.operand, Some(Operand { index: 0, kind: OperandKind::Chars, span: 30..34 }));
+        let node = table.node_at(36).unwrap();
+        assert_eq!(node.operand, Some(Operand { index: 1, kind: OperandKind::Dots, span: 35..37 }));
+        let node = table.node_at(40).unwrap();
+        assert_eq!((node.line, node.operand), (2, None));
+        let node = table.node_at(68).unwrap();
+        assert_eq!(node.rule,
+                   Some(&Rule::Joinword { word: "haha",
+                                          dots: vec![BrailleDot::DOT1 | BrailleDot::DOT2 | BrailleDot::DOT3] }));
+        assert_eq!(table.node_at(69), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "chardef", feature = "translation"))]
+    fn invalid_line_test() {
+        let source = "display a 1\ndisplay b\nfoo a 1\r\njoinword to 235\n";
+        let (rest, table) = Table::parse(source).unwrap();
+        assert_eq!(rest, "");
+        assert_eq!(table.lines()[1], Line::Invalid { text: "display b" });
+        assert_eq!(table.lines()[2], Line::Invalid { text: "foo a 1" });
+        assert_eq!(table.span(2), Some(22..29));
+        assert!(matches!(table.lines()[0], Line::Rule { rule: Rule::Display { .. }, .. }));
+        assert_eq!(table.node_at_position(3, 9).unwrap().rule,
+                   Some(&Rule::Joinword { word: "to", dots: vec![BrailleDot::DOT2 | BrailleDot::DOT3 | BrailleDot::DOT5] }));
+        assert_eq!(table.operands(1), vec![]);
+        let (rest, table) = Table::parse("display a 1\ndisplay b").unwrap();
+        assert_eq!((rest, table.lines().len()), ("", 2));
+        assert_eq!(table.lines()[1], Line::Invalid { text: "display b" });
+        let (rest, table) = Table::parse("display a 1\ndisplay b 12").unwrap();
+        assert_eq!((rest, table.lines().len()), ("", 2));
+        assert!(matches!(table.lines()[1], Line::Rule { rule: Rule::Display { chars: "b", .. }, .. }));
+        assert_eq!(table.span(1), Some(12..24));
+        assert_eq!(table.operands(1).len(), 2);
+    }
+
+    #[test]
+    fn line_index_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        assert_eq!(table.line_index().line_column(60), Some(LineColumn { line: 3, column: 9 }));
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn operands_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        assert_eq!(table.operands(2),
+                   vec![Operand { index: 0, kind: OperandKind::Chars, span: 30..34 },
+                        Operand { index: 1, kind: OperandKind::Dots, span: 35..37 }]);
+        assert_eq!(table.operands(0), vec![]);
+        assert_eq!(table.operands(4), vec![]);
+    }
+
+    #[test]
+    fn shared_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        let table = Arc::new(table);
+        let handles: Vec<_> = (0..4)
+            .map(|line| {
+                let table = Arc::clone(&table);
+                thread::spawn(move || (table.line_index().line_column(60), table.span(line)))
+            })
+            .collect();
+        let results: Vec<_> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        assert!(results.iter().all(|(position, _)| *position == Some(LineColumn { line: 3, column: 9 })));
+        assert_eq!(results[2].1, Some(13..49));
+    }
+
+    #[test]
+    #[cfg(feature = "translation")]
+    fn node_at_position_test() {
+        let (_, table) = Table::parse(SOURCE).unwrap();
+        assert_eq!(table.node_at_position(3, 9).unwrap().operand,
+                   Some(Operand { index: 0, kind: OperandKind::Chars, span: 60..64 }));
+        assert_eq!(table.node_at_position(1, 1), None);
+        assert_eq!(table.node_at_position(4, 0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "chardef")]
+    fn compiled_shared_test() {
+        // an owned source, e.g. as read from a file
+        let source = ["display a 1", "display b 12", "foo"].join("\n");
+        let shared = CompiledTable::shared(source.clone());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = SharedTable::clone(&shared);
+                thread::spawn(move || {
+                    let (_, table) = shared.table().unwrap();
+                    (shared.display_map().map.get(&'b').cloned(), table.lines().len(), shared.diagnostics().len())
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), (Some(vec![BrailleDot::DOT1 | BrailleDot::DOT2]), 3, 1));
+        }
+        assert_eq!(shared.source(), source);
+    }
+}